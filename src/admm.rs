@@ -0,0 +1,22 @@
+//! Alternating direction method of multipliers (ADMM) for dual problems with additional linear constraints
+//!
+//! Besides the constraint `Σ aᵢ = 0` (offset `b`), the coefficients have to satisfy constraints `C a = d` or `C a ≤ d`,
+//! e.g., for fairness between groups of samples or budgets on the coefficients of a group.
+//! With a copy `z ∈ Z` of `C a` and the scaled multipliers `w`, every step consists of
+//!
+//! * the proximal step `a = argmin f(a) + ρ/2 ‖C a - z + w‖²` (see [`Inner`]),
+//! * the projection `z = Proj_Z(C a + w)`,
+//! * the multiplier update `w = w + C a - z`.
+//!
+//! The quadratic penalty is absorbed into the kernel matrix `K + λ ρ CᵀC` and the linear part into the dual loss functions,
+//! such that the proximal step is a training problem of the usual form.
+//! By default, ρ is adapted to balance the primal residual `‖C a - z‖` and the dual residual `ρ ‖Cᵀ(z - z_old)‖` (see [`Params::adaptive_rho`]).
+
+mod params;
+pub use params::{Inner, Params};
+mod constraint;
+pub use constraint::{Constraint, ConstraintKind};
+mod augmented;
+mod boxqp;
+mod solve;
+pub use solve::{solve, Solution};
@@ -0,0 +1,116 @@
+use super::Constraint;
+use crate::kernel::Kernel;
+
+/// Kernel matrix `K + factor · CᵀC` of a base kernel matrix `K` and the constraint matrix `C`
+pub struct AugmentedKernel<'a> {
+    base: &'a mut dyn Kernel,
+    constraints: &'a [Constraint],
+    factor: f64,
+}
+
+impl<'a> AugmentedKernel<'a> {
+    /// Creates an [`AugmentedKernel`].
+    pub fn new(base: &'a mut dyn Kernel, constraints: &'a [Constraint], factor: f64) -> Self {
+        AugmentedKernel {
+            base,
+            constraints,
+            factor,
+        }
+    }
+
+    /// Updates the factor of the added term.
+    pub fn set_factor(&mut self, factor: f64) {
+        self.factor = factor;
+    }
+
+    /// Adds the ith row of `factor · CᵀC` with entries according to `active_set` to `ki`.
+    fn add_term(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        for constraint in self.constraints.iter() {
+            let ci = self.factor * constraint.coef[i];
+            if ci == 0.0 {
+                continue;
+            }
+            for (kij, &j) in ki.iter_mut().zip(active_set.iter()) {
+                *kij += ci * constraint.coef[j];
+            }
+        }
+    }
+}
+
+impl Kernel for AugmentedKernel<'_> {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        self.base.compute_row(i, ki, active_set);
+        self.add_term(i, ki, active_set);
+    }
+
+    fn size(&self) -> usize {
+        self.base.size()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        self.base.diag(i)
+            + self
+                .constraints
+                .iter()
+                .map(|constraint| self.factor * constraint.coef[i] * constraint.coef[i])
+                .sum::<f64>()
+    }
+
+    fn restrict_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.restrict_active(old, new);
+    }
+
+    fn set_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.set_active(old, new);
+    }
+
+    fn use_rows(&mut self, idxs: &[usize], active_set: &[usize], fun: &mut dyn FnMut(Vec<&[f64]>)) {
+        // use the rows of the base kernel (e.g., from its cache)
+        let mut kidxs: Vec<Vec<f64>> = Vec::with_capacity(idxs.len());
+        self.base
+            .use_rows(idxs, active_set, &mut |kis: Vec<&[f64]>| {
+                kidxs.extend(kis.into_iter().map(|ki| ki.to_vec()));
+            });
+        for (ki, &i) in kidxs.iter_mut().zip(idxs.iter()) {
+            self.add_term(i, ki, active_set);
+        }
+        fun(kidxs.iter().map(|ki| ki.as_slice()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{gaussian, CachedKernel};
+    use crate::testing::{rows, samples};
+
+    #[test]
+    fn rows_use_cache_of_base() {
+        let x = samples(10);
+        let constraints = [
+            Constraint::equal((0..10).map(|i| (i % 3) as f64).collect(), 0.0),
+            Constraint::less_equal(vec![1.0; 10], 1.0),
+        ];
+        let mut base = CachedKernel::from(Box::new(gaussian::from_vecs(rows(&x), 1.0)), 10);
+        let active_set: Vec<usize> = (0..10).rev().collect();
+        let idxs = [3, 7, 3];
+        let mut expected = vec![vec![0.0; 10]; idxs.len()];
+        {
+            let kernel = AugmentedKernel::new(&mut base, &constraints, 0.5);
+            for (ki, &i) in expected.iter_mut().zip(idxs.iter()) {
+                kernel.compute_row(i, ki, &active_set);
+            }
+        }
+        for _pass in 0..2 {
+            let mut kernel = AugmentedKernel::new(&mut base, &constraints, 0.5);
+            kernel.use_rows(&idxs, &active_set, &mut |kis| {
+                for (ki, expected_i) in kis.iter().zip(expected.iter()) {
+                    for (kij, expected_ij) in ki.iter().zip(expected_i.iter()) {
+                        assert!((kij - expected_ij).abs() < 1e-14);
+                    }
+                }
+            });
+        }
+        assert!(base.stats().hits > 0);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::kernel::Kernel;
+use crate::problem::DualProblem;
+use crate::status::Status;
+
+/// Uses cyclic coordinate descent to solve the given training problem without the constraint `Σ aᵢ = 0` starting from (and updating) `status`.
+///
+/// Returns the number of passes over the variables.
+pub fn solve(
+    problem: &dyn DualProblem,
+    kernel: &dyn Kernel,
+    status: &mut Status,
+    max_steps: usize,
+    tol: f64,
+) -> usize {
+    let n = problem.size();
+    let lambda = problem.lambda();
+    let full_set: Vec<usize> = (0..n).collect();
+    let mut ki = vec![0.0; n];
+    let mut step = 0;
+    while step < max_steps {
+        step += 1;
+        let mut violation: f64 = 0.0;
+        for i in 0..n {
+            let ai = status.a[i];
+            let gi = status.ka[i] + problem.d_dloss(i, ai);
+            let (lb, ub) = (problem.lb(i), problem.ub(i));
+            let pgi = if ai <= lb {
+                f64::min(gi, 0.0)
+            } else if ai >= ub {
+                f64::max(gi, 0.0)
+            } else {
+                gi
+            };
+            violation = f64::max(violation, pgi.abs());
+            let qi = kernel.diag(i) / lambda + problem.d2_dloss(i, ai);
+            if pgi == 0.0 || qi <= 0.0 {
+                continue;
+            }
+            let dai = f64::clamp(ai - gi / qi, lb, ub) - ai;
+            if dai == 0.0 {
+                continue;
+            }
+            status.a[i] += dai;
+            kernel.compute_row(i, &mut ki, &full_set);
+            for (kak, kik) in status.ka.iter_mut().zip(ki.iter()) {
+                *kak += dai * kik / lambda;
+            }
+        }
+        if lambda * violation < tol {
+            break;
+        }
+    }
+    for i in 0..n {
+        status.g[i] = status.ka[i] + problem.d_dloss(i, status.a[i]);
+    }
+    step
+}
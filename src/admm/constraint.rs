@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Type of a linear constraint
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ConstraintKind {
+    /// Equality constraint `Σᵢ cᵢ aᵢ = d`
+    Equal,
+    /// Inequality constraint `Σᵢ cᵢ aᵢ ≤ d`
+    LessEqual,
+}
+
+/// Linear constraint on the coefficient vector
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Constraint {
+    /// Coefficients `cᵢ` (one per variable)
+    pub coef: Vec<f64>,
+    /// Right-hand side `d`
+    pub rhs: f64,
+    /// Type of the constraint
+    pub kind: ConstraintKind,
+}
+
+impl Constraint {
+    /// Creates the constraint `Σᵢ cᵢ aᵢ = d`.
+    pub fn equal(coef: Vec<f64>, rhs: f64) -> Self {
+        Constraint {
+            coef,
+            rhs,
+            kind: ConstraintKind::Equal,
+        }
+    }
+
+    /// Creates the constraint `Σᵢ cᵢ aᵢ ≤ d`.
+    pub fn less_equal(coef: Vec<f64>, rhs: f64) -> Self {
+        Constraint {
+            coef,
+            rhs,
+            kind: ConstraintKind::LessEqual,
+        }
+    }
+
+    /// Creates the constraint `Σᵢ cᵢ aᵢ ≥ d` (stored as `Σᵢ -cᵢ aᵢ ≤ -d`).
+    pub fn greater_equal(coef: Vec<f64>, rhs: f64) -> Self {
+        Self::less_equal(coef.iter().map(|ci| -ci).collect(), -rhs)
+    }
+
+    /// Computes `Σᵢ cᵢ aᵢ`.
+    pub fn value(&self, a: &[f64]) -> f64 {
+        self.coef.iter().zip(a.iter()).map(|(ci, ai)| ci * ai).sum()
+    }
+
+    /// Projects a value of `Σᵢ cᵢ aᵢ` onto the feasible set.
+    pub fn project(&self, value: f64) -> f64 {
+        match self.kind {
+            ConstraintKind::Equal => self.rhs,
+            ConstraintKind::LessEqual => f64::min(value, self.rhs),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::smo;
+
+/// Solver for the proximal step
+#[derive(Clone, Debug)]
+pub enum Inner {
+    /// SMO method on the augmented problem (see [`smo::solve_with_status`]), which keeps the constraint `Σ aᵢ = 0`
+    Smo(smo::Params),
+    /// Coordinate descent on the augmented problem with box constraints only,
+    /// where the constraint `Σ aᵢ = 0` is handled like the additional constraints
+    BoxQp {
+        /// Maximum number of passes over the variables
+        max_steps: usize,
+        /// Termination tolerance
+        tol: f64,
+    },
+}
+
+/// Parameters of the ADMM method
+#[derive(Clone, Debug)]
+pub struct Params {
+    /// Termination tolerance for the primal and dual residuals
+    pub tol: f64,
+    /// Maximum number of steps
+    pub max_steps: usize,
+    /// Frequency of logging or `0` for no logging
+    pub verbose: usize,
+    /// Time limit (in seconds)
+    pub time_limit: f64,
+    /// (Initial) penalty parameter ρ of the augmented Lagrangian
+    pub rho: f64,
+    /// Decides whether or not ρ is adapted to balance the primal and dual residuals.
+    pub adaptive_rho: bool,
+    /// Solver for the proximal step
+    pub inner: Inner,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            tol: 1e-4,
+            max_steps: 1000,
+            verbose: 0,
+            time_limit: f64::INFINITY,
+            rho: 1.0,
+            adaptive_rho: true,
+            inner: Inner::Smo(smo::Params::new().with_tol(1e-6)),
+        }
+    }
+
+    /// Updates the verbosity level.
+    pub fn with_verbose(mut self, verbose: usize) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Updates the termination tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Updates the time limit.
+    pub fn with_time_limit(mut self, time_limit: f64) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Updates the maximum number of steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Updates the penalty parameter ρ.
+    pub fn with_rho(mut self, rho: f64) -> Self {
+        assert!(rho > 0.0, "rho should be positive");
+        self.rho = rho;
+        self
+    }
+
+    /// Updates the decision whether or not ρ is adapted.
+    pub fn with_adaptive_rho(mut self, adaptive_rho: bool) -> Self {
+        self.adaptive_rho = adaptive_rho;
+        self
+    }
+
+    /// Updates the solver for the proximal step.
+    pub fn with_inner(mut self, inner: Inner) -> Self {
+        self.inner = inner;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
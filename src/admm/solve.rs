@@ -0,0 +1,342 @@
+use super::augmented::AugmentedKernel;
+use super::{boxqp, Constraint, Inner, Params};
+use crate::kernel::Kernel;
+use crate::problem::{DualProblem, Shifted};
+use crate::smo;
+use crate::status::{Status, StatusCode};
+use crate::time::{now, until_now};
+
+/// Ratio of the primal and dual residuals leading to an adaptation of ρ
+const RESIDUAL_RATIO: f64 = 10.0;
+/// Factor by which ρ is adapted
+const RHO_FACTOR: f64 = 2.0;
+
+/// Result of the ADMM method
+#[derive(Clone, Debug)]
+pub struct Solution {
+    /// Final status (with the kernel product of the original kernel matrix)
+    pub status: Status,
+    /// Multipliers `uₖ` of the additional constraints
+    ///
+    /// The decision values of the training samples are `tᵢ = kaᵢ + b + Σₖ uₖ cₖᵢ`.
+    pub multipliers: Vec<f64>,
+}
+
+/// Recomputes the status of the original problem from the status of the proximal step and the constraint values `ca`.
+fn update_status(
+    problem: &dyn DualProblem,
+    constraints: &[Constraint],
+    ca: &[f64],
+    rho: f64,
+    inner: &Status,
+    status: &mut Status,
+) {
+    status.a.copy_from_slice(&inner.a);
+    status.ka.copy_from_slice(&inner.ka);
+    for (constraint, cak) in constraints.iter().zip(ca.iter()) {
+        for (kai, ci) in status.ka.iter_mut().zip(constraint.coef.iter()) {
+            *kai -= rho * ci * cak;
+        }
+    }
+    status.asum = 0.0;
+    for i in 0..problem.size() {
+        status.g[i] = status.ka[i] + problem.d_dloss(i, status.a[i]);
+        status.asum += problem.sign(i) * status.a[i];
+    }
+    status.value = -problem.objective(status);
+}
+
+/// Uses ADMM to solve the given training problem with additional linear `constraints` starting from the default initial point.
+///
+/// The coefficients of the constraints refer to the variables of the problem (see [`ProblemBase::size`](crate::problem::base::ProblemBase::size)).
+pub fn solve(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    constraints: &[Constraint],
+    params: &Params,
+    callback: Option<&dyn Fn(&Status) -> bool>,
+) -> Solution {
+    assert!(
+        !problem.has_max_asum(),
+        "bound on the 1-norm is not supported"
+    );
+    let n = problem.size();
+    assert!(
+        constraints
+            .iter()
+            .all(|constraint| constraint.coef.len() == n),
+        "constraints should have one coefficient per variable"
+    );
+    let start = now();
+    let lambda = problem.lambda();
+    let mut rho = params.rho;
+
+    // the box QP handles the sum constraint like the additional constraints
+    let mut all = constraints.to_vec();
+    if matches!(params.inner, Inner::BoxQp { .. }) {
+        all.push(Constraint::equal(vec![1.0; n], 0.0));
+    }
+    let m = all.len();
+    let mut z: Vec<f64> = all
+        .iter()
+        .map(|constraint| constraint.project(0.0))
+        .collect();
+    let mut w = vec![0.0; m];
+    let mut ca = vec![0.0; m];
+
+    // the status of the proximal step contains the product with the augmented kernel matrix
+    let mut inner = Status::new(n);
+    let mut kernel = AugmentedKernel::new(kernel, &all, lambda * rho);
+    let mut status = Status::new(n);
+    update_status(problem, &all, &ca, rho, &inner, &mut status);
+    let mut step: usize = 0;
+
+    if params.verbose > 0 {
+        println!(
+            "{:>10} {:>10} {:>10} {:>10} {:>10}",
+            "step", "time", "primal", "dual", "obj",
+        )
+    }
+
+    loop {
+        // update steps and time
+        status.opt_status.steps = step;
+        let elapsed = until_now(start);
+        status.opt_status.time = elapsed;
+
+        // handle step limit
+        if step >= params.max_steps {
+            status.opt_status.code = StatusCode::MaxSteps;
+            break;
+        }
+
+        // handle time limit
+        if params.time_limit > 0.0 && elapsed >= params.time_limit {
+            status.opt_status.code = StatusCode::TimeLimit;
+            break;
+        }
+
+        // handle callback
+        if let Some(callback_fn) = callback {
+            if callback_fn(&status) {
+                status.opt_status.code = StatusCode::Callback;
+                break;
+            }
+        };
+
+        // proximal step with the linear term ρ Cᵀ(w - z)
+        let mut linear = vec![0.0; n];
+        for (constraint, (wk, zk)) in all.iter().zip(w.iter().zip(z.iter())) {
+            let factor = rho * (wk - zk);
+            if factor == 0.0 {
+                continue;
+            }
+            for (pi, ci) in linear.iter_mut().zip(constraint.coef.iter()) {
+                *pi += factor * ci;
+            }
+        }
+        let shifted = Shifted::new(problem, linear);
+        inner.value = -shifted.objective(&inner);
+        match &params.inner {
+            Inner::Smo(smo_params) => {
+                inner = smo::solve_with_status(inner, &shifted, &mut kernel, smo_params, None);
+            }
+            Inner::BoxQp { max_steps, tol } => {
+                boxqp::solve(&shifted, &kernel, &mut inner, *max_steps, *tol);
+            }
+        }
+
+        // projection and multiplier update
+        let mut primal_sqr = 0.0;
+        let mut ct_dz = vec![0.0; n];
+        for k in 0..m {
+            ca[k] = all[k].value(&inner.a);
+            let zk = all[k].project(ca[k] + w[k]);
+            let dzk = zk - z[k];
+            if dzk != 0.0 {
+                for (vi, ci) in ct_dz.iter_mut().zip(all[k].coef.iter()) {
+                    *vi += dzk * ci;
+                }
+            }
+            z[k] = zk;
+            w[k] += ca[k] - zk;
+            primal_sqr += (ca[k] - zk).powi(2);
+        }
+        let primal = primal_sqr.sqrt();
+        let dual = rho * ct_dz.iter().map(|vi| vi * vi).sum::<f64>().sqrt();
+        step += 1;
+
+        update_status(problem, &all, &ca, rho, &inner, &mut status);
+        status.b = match params.inner {
+            Inner::Smo(_) => inner.b,
+            Inner::BoxQp { .. } => rho * w[m - 1],
+        };
+        status.opt_status.violation = f64::max(primal, dual);
+
+        // handle progress output
+        let optimal = primal <= params.tol && dual <= params.tol;
+        if params.verbose > 0 && (step % params.verbose == 0 || optimal) {
+            println!(
+                "{:10} {:10.2} {:10.3e} {:10.3e} {:10.6}",
+                step,
+                until_now(start),
+                primal,
+                dual,
+                status.value,
+            )
+        }
+
+        // check for optimality
+        if optimal {
+            status.opt_status.code = StatusCode::Optimal;
+            status.opt_status.steps = step;
+            break;
+        }
+
+        // balance the residuals (keeping the multipliers ρ w and the product with the original kernel matrix)
+        if params.adaptive_rho {
+            let factor = if primal > RESIDUAL_RATIO * dual {
+                RHO_FACTOR
+            } else if dual > RESIDUAL_RATIO * primal {
+                1.0 / RHO_FACTOR
+            } else {
+                1.0
+            };
+            if factor != 1.0 {
+                let rho_new = factor * rho;
+                for (constraint, cak) in all.iter().zip(ca.iter()) {
+                    for (kai, ci) in inner.ka.iter_mut().zip(constraint.coef.iter()) {
+                        *kai += (rho_new - rho) * ci * cak;
+                    }
+                }
+                w.iter_mut().for_each(|wk| *wk /= factor);
+                kernel.set_factor(lambda * rho_new);
+                rho = rho_new;
+            }
+        }
+    }
+    status.opt_status.time = until_now(start);
+    Solution {
+        status,
+        multipliers: w[..constraints.len()].iter().map(|wk| rho * wk).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{Classification, Params as ProblemParams};
+    use crate::testing::{classification, rows};
+
+    fn inner_solvers() -> [Inner; 2] {
+        [
+            Inner::Smo(smo::Params::new().with_tol(1e-8)),
+            Inner::BoxQp {
+                max_steps: 10000,
+                tol: 1e-8,
+            },
+        ]
+    }
+
+    #[test]
+    fn equal_constraint_is_satisfied() {
+        let (x, y) = classification(30);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.1));
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let reference = smo::solve(
+            &problem,
+            &mut kernel,
+            &smo::Params::new().with_tol(1e-8),
+            None,
+        );
+
+        // halve the sum of the coefficients of the first samples
+        let coef: Vec<f64> = (0..y.len())
+            .map(|i| if i < 10 { 1.0 } else { 0.0 })
+            .collect();
+        let rhs = 0.5
+            * coef
+                .iter()
+                .zip(reference.a.iter())
+                .map(|(ci, ai)| ci * ai)
+                .sum::<f64>();
+        let constraints = [Constraint::equal(coef, rhs)];
+        for inner in inner_solvers() {
+            let params = Params::new().with_tol(1e-6).with_inner(inner);
+            let solution = solve(&problem, &mut kernel, &constraints, &params, None);
+            assert!(matches!(
+                solution.status.opt_status.code,
+                StatusCode::Optimal
+            ));
+            let value = constraints[0].value(&solution.status.a);
+            assert!((value - rhs).abs() < 1e-4);
+            assert!(solution.status.value < reference.value);
+        }
+    }
+
+    #[test]
+    fn binding_inequality_constraints() {
+        let (x, y) = classification(30);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.1));
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let reference = smo::solve(
+            &problem,
+            &mut kernel,
+            &smo::Params::new().with_tol(1e-8),
+            None,
+        );
+
+        // move the sum of the coefficients of the first samples by a quarter of its value in both directions
+        let coef: Vec<f64> = (0..y.len())
+            .map(|i| if i < 10 { 1.0 } else { 0.0 })
+            .collect();
+        let sum = Constraint::equal(coef.clone(), 0.0).value(&reference.a);
+        assert!(sum > 0.0);
+        for constraint in [
+            Constraint::less_equal(coef.clone(), 0.75 * sum),
+            Constraint::greater_equal(coef.clone(), 1.25 * sum),
+        ] {
+            let constraints = [constraint];
+            for inner in inner_solvers() {
+                let params = Params::new().with_tol(1e-6).with_inner(inner);
+                let solution = solve(&problem, &mut kernel, &constraints, &params, None);
+                assert!(matches!(
+                    solution.status.opt_status.code,
+                    StatusCode::Optimal
+                ));
+                let constraint = &constraints[0];
+                let value = constraint.value(&solution.status.a);
+                assert!((value - constraint.rhs).abs() < 1e-4);
+                assert!(solution.multipliers[0] > 0.0);
+                assert!(solution.status.value < reference.value);
+            }
+        }
+    }
+
+    #[test]
+    fn inactive_constraint_agrees_with_smo() {
+        let (x, y) = classification(30);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.1));
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let reference = smo::solve(
+            &problem,
+            &mut kernel,
+            &smo::Params::new().with_tol(1e-8),
+            None,
+        );
+
+        let constraints = [Constraint::less_equal(vec![1.0; y.len()], 100.0)];
+        for inner in inner_solvers() {
+            let params = Params::new().with_tol(1e-6).with_inner(inner);
+            let solution = solve(&problem, &mut kernel, &constraints, &params, None);
+            assert!(matches!(
+                solution.status.opt_status.code,
+                StatusCode::Optimal
+            ));
+            assert!(solution.multipliers[0].abs() < 1e-6);
+            let value = solution.status.value;
+            assert!((value - reference.value).abs() < 1e-4 * (1.0 + reference.value.abs()));
+        }
+    }
+}
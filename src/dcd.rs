@@ -0,0 +1,12 @@
+//! Dual coordinate descent for linear kernels (as in LIBLINEAR)
+//!
+//! The weight vector `w = Σ aᵢ xᵢ / λ` is kept explicitly such that a coordinate step only costs `O(nnz(xᵢ))` instead of a kernel row.
+//! In contrast to the other solvers, the offset is regularized: each sample is extended by a constant feature (see [`Params::bias`]),
+//! which removes the equality constraint `Σ aᵢ = 0` and allows for updating single coordinates.
+
+mod params;
+pub use params::Params;
+mod row;
+pub use row::Row;
+mod solve;
+pub use solve::{solve, solve_with_status};
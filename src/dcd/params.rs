@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Parameters of the dual coordinate descent method
+pub struct Params {
+    /// Termination tolerance
+    pub tol: f64,
+    /// Maximum number of steps (passes over the active variables)
+    pub max_steps: usize,
+    /// Frequency of logging or `0` for no logging
+    pub verbose: usize,
+    /// Time limit (in seconds)
+    pub time_limit: f64,
+    /// Decides whether or not variables at their bounds are removed from the passes (shrinking).
+    pub shrinking: bool,
+    /// Value of the constant feature appended to each sample (`0` for no offset)
+    pub bias: f64,
+    /// Random seed for the permutations of the variables
+    pub seed: u64,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            tol: 1e-3,
+            max_steps: 1000,
+            verbose: 0,
+            time_limit: f64::INFINITY,
+            shrinking: true,
+            bias: 1.0,
+            seed: 0,
+        }
+    }
+
+    /// Updates the verbosity level.
+    pub fn with_verbose(mut self, verbose: usize) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Updates the termination tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Updates the time limit.
+    pub fn with_time_limit(mut self, time_limit: f64) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Updates the maximum number of steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Enables or disables shrinking.
+    pub fn with_shrinking(mut self, shrinking: bool) -> Self {
+        self.shrinking = shrinking;
+        self
+    }
+
+    /// Updates the value of the constant feature.
+    pub fn with_bias(mut self, bias: f64) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Updates the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
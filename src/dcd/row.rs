@@ -0,0 +1,51 @@
+use crate::kernel::sparse::SparseRow;
+
+/// A (dense or sparse) sample for the dual coordinate descent method
+pub trait Row {
+    /// Returns the required length of the weight vector (largest feature index plus one).
+    fn dim(&self) -> usize;
+    /// Computes the inner product with the weight vector `w`.
+    fn dot_weights(&self, w: &[f64]) -> f64;
+    /// Adds the sample scaled by `factor` to the weight vector `w`.
+    fn add_to_weights(&self, w: &mut [f64], factor: f64);
+    /// Returns the squared Euclidean norm.
+    fn norm_sqr(&self) -> f64;
+}
+
+impl Row for &[f64] {
+    fn dim(&self) -> usize {
+        self.len()
+    }
+    fn dot_weights(&self, w: &[f64]) -> f64 {
+        self.iter().zip(w).map(|(xk, wk)| xk * wk).sum()
+    }
+    fn add_to_weights(&self, w: &mut [f64], factor: f64) {
+        for (wk, xk) in w.iter_mut().zip(self.iter()) {
+            *wk += factor * xk;
+        }
+    }
+    fn norm_sqr(&self) -> f64 {
+        self.iter().map(|xk| xk * xk).sum()
+    }
+}
+
+impl Row for SparseRow {
+    fn dim(&self) -> usize {
+        self.indices().last().map_or(0, |&k| k + 1)
+    }
+    fn dot_weights(&self, w: &[f64]) -> f64 {
+        self.indices()
+            .iter()
+            .zip(self.values())
+            .map(|(&k, xk)| xk * w[k])
+            .sum()
+    }
+    fn add_to_weights(&self, w: &mut [f64], factor: f64) {
+        for (&k, xk) in self.indices().iter().zip(self.values()) {
+            w[k] += factor * xk;
+        }
+    }
+    fn norm_sqr(&self) -> f64 {
+        SparseRow::norm_sqr(self)
+    }
+}
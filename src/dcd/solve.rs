@@ -0,0 +1,278 @@
+use crate::problem::DualProblem;
+use crate::status::{Status, StatusCode};
+use crate::time::{now, until_now};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use super::{Params, Row};
+
+/// Uses dual coordinate descent to solve the given training problem (with the linear kernel on `data`) starting from the default initial point.
+pub fn solve<R: Row>(
+    problem: &dyn DualProblem,
+    data: &[R],
+    params: &Params,
+    callback: Option<&dyn Fn(&Status) -> bool>,
+) -> Status {
+    let n = problem.size();
+    let mut status = Status::new(n);
+    for k in 0..n {
+        status.value -= problem.dloss(k, 0.0);
+    }
+    solve_with_status(status, problem, data, params, callback)
+}
+
+/// Recomputes the kernel product, the offset, the gradient, the 1-norm and the objective function value from the weight vector.
+fn update_status<R: Row>(
+    problem: &dyn DualProblem,
+    data: &[R],
+    w: &[f64],
+    bias: f64,
+    status: &mut Status,
+) {
+    let dim = w.len() - 1;
+    let m = data.len();
+    let mut sum = 0.0;
+    status.asum = 0.0;
+    for i in 0..problem.size() {
+        status.ka[i] = data[i % m].dot_weights(&w[..dim]);
+        status.g[i] = status.ka[i] + problem.d_dloss(i, status.a[i]);
+        status.asum += problem.sign(i) * status.a[i];
+        sum += status.a[i];
+    }
+    status.b = bias * w[dim];
+    status.value = -problem.objective(status) - 0.5 * status.b * sum;
+}
+
+/// Uses dual coordinate descent to solve the given training problem (with the linear kernel on `data`) starting from a particular [`Status`].
+///
+/// Only the coefficients of the status are used, since the weight vector is recomputed from them.
+/// The `i`th variable belongs to the sample `data[i % data.len()]` and the dual loss functions have to be quadratic.
+pub fn solve_with_status<R: Row>(
+    status: Status,
+    problem: &dyn DualProblem,
+    data: &[R],
+    params: &Params,
+    callback: Option<&dyn Fn(&Status) -> bool>,
+) -> Status {
+    assert!(problem.is_quad(), "dual loss functions should be quadratic");
+    assert!(
+        !problem.has_max_asum(),
+        "bound on the 1-norm is not supported"
+    );
+    let mut status = status;
+    let start = now();
+
+    let n = problem.size();
+    let m = data.len();
+    assert!(n > 0 && m > 0, "problem and data should not be empty");
+    let lambda = problem.lambda();
+    let bias = params.bias;
+
+    // weight vector (the last entry belongs to the constant feature)
+    let dim = data.iter().map(|x| x.dim()).max().unwrap_or(0);
+    let mut w = vec![0.0; dim + 1];
+    for (i, &ai) in status.a.iter().enumerate() {
+        if ai != 0.0 {
+            data[i % m].add_to_weights(&mut w[..dim], ai / lambda);
+            w[dim] += ai * bias / lambda;
+        }
+    }
+    let diags: Vec<f64> = (0..m)
+        .map(|i| (data[i].norm_sqr() + bias * bias) / lambda)
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let mut active_set: Vec<usize> = (0..n).collect();
+    // extreme projected gradients of the previous step (used for shrinking)
+    let mut pg_max_old = f64::INFINITY;
+    let mut pg_min_old = f64::NEG_INFINITY;
+    let mut step: usize = 0;
+
+    if params.verbose > 0 {
+        println!(
+            "{:>10} {:>10} {:>10} {:>8} / size",
+            "step", "time", "violation", "|active|",
+        )
+    }
+
+    loop {
+        // update steps and time
+        status.opt_status.steps = step;
+        let elapsed = until_now(start);
+        status.opt_status.time = elapsed;
+
+        // handle step limit
+        if step >= params.max_steps {
+            status.opt_status.code = StatusCode::MaxSteps;
+            break;
+        }
+
+        // handle time limit
+        if params.time_limit > 0.0 && elapsed >= params.time_limit {
+            status.opt_status.code = StatusCode::TimeLimit;
+            break;
+        }
+
+        // handle callback
+        if let Some(callback_fn) = callback {
+            update_status(problem, data, &w, bias, &mut status);
+            if callback_fn(&status) {
+                status.opt_status.code = StatusCode::Callback;
+                break;
+            }
+        };
+
+        // pass over the active variables in random order
+        active_set.shuffle(&mut rng);
+        let mut pg_max = f64::NEG_INFINITY;
+        let mut pg_min = f64::INFINITY;
+        let mut idx = 0;
+        while idx < active_set.len() {
+            let i = active_set[idx];
+            let xi = &data[i % m];
+            let ai = status.a[i];
+            let gi = xi.dot_weights(&w[..dim]) + bias * w[dim] + problem.d_dloss(i, ai);
+            let (lb, ub) = (problem.lb(i), problem.ub(i));
+            let pgi = if ai <= lb {
+                if params.shrinking && gi > pg_max_old {
+                    active_set.swap_remove(idx);
+                    continue;
+                }
+                f64::min(gi, 0.0)
+            } else if ai >= ub {
+                if params.shrinking && gi < pg_min_old {
+                    active_set.swap_remove(idx);
+                    continue;
+                }
+                f64::max(gi, 0.0)
+            } else {
+                gi
+            };
+            pg_max = f64::max(pg_max, pgi);
+            pg_min = f64::min(pg_min, pgi);
+            let qi = diags[i % m] + problem.d2_dloss(i, ai);
+            if pgi != 0.0 && qi > 0.0 {
+                let ai_new = f64::min(f64::max(ai - gi / qi, lb), ub);
+                let dai = ai_new - ai;
+                status.a[i] = ai_new;
+                xi.add_to_weights(&mut w[..dim], dai / lambda);
+                w[dim] += dai * bias / lambda;
+            }
+            idx += 1;
+        }
+        step += 1;
+        status.opt_status.violation = pg_max - pg_min;
+
+        // handle progress output
+        let optimal = problem.is_optimal(&status, params.tol);
+        if params.verbose > 0 && (step % params.verbose == 0 || optimal) {
+            println!(
+                "{:10} {:10.2} {:10.6} {:8} / {}",
+                step,
+                until_now(start),
+                status.opt_status.violation,
+                active_set.len(),
+                n
+            )
+        }
+
+        // check for optimality (on all variables)
+        if optimal {
+            if active_set.len() == n {
+                status.opt_status.code = StatusCode::Optimal;
+                status.opt_status.steps = step;
+                break;
+            }
+            active_set = (0..n).collect();
+            pg_max_old = f64::INFINITY;
+            pg_min_old = f64::NEG_INFINITY;
+            continue;
+        }
+        pg_max_old = if pg_max > 0.0 { pg_max } else { f64::INFINITY };
+        pg_min_old = if pg_min < 0.0 {
+            pg_min
+        } else {
+            f64::NEG_INFINITY
+        };
+    }
+    update_status(problem, data, &w, bias, &mut status);
+    status.opt_status.time = until_now(start);
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::sparse::SparseRow;
+    use crate::problem::{Classification, Params as ProblemParams, Regression, LSSVM};
+    use crate::testing::{classification, regression, rows};
+
+    /// Checks the optimality conditions of the problem with the constant feature (offset `b`).
+    fn check_optimal(problem: &dyn DualProblem, status: &Status, tol: f64) {
+        for i in 0..problem.size() {
+            let (ai, gi) = (status.a[i], status.g[i] + status.b);
+            if ai > problem.lb(i) {
+                assert!(gi < tol);
+            }
+            if ai < problem.ub(i) {
+                assert!(gi > -tol);
+            }
+        }
+    }
+
+    #[test]
+    fn optimal_for_dense_and_sparse_rows() {
+        let (x, y) = classification(40);
+        let params = Params::new().with_tol(1e-8).with_max_steps(100000);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.1));
+        let dense = rows(&x);
+        let status = solve(&problem, &dense, &params, None);
+        assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+        check_optimal(&problem, &status, 1e-6);
+
+        let sparse: Vec<SparseRow> = x.iter().map(|xi| SparseRow::from_dense(xi)).collect();
+        let status_sparse = solve(&problem, &sparse, &params, None);
+        for (ai, bi) in status.a.iter().zip(status_sparse.a.iter()) {
+            assert!((ai - bi).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn optimal_without_shrinking() {
+        let (x, y) = classification(40);
+        let params = Params::new()
+            .with_tol(1e-8)
+            .with_max_steps(100000)
+            .with_shrinking(false);
+        let problem = LSSVM::new(&y, ProblemParams::new().with_lambda(0.5));
+        let dense = rows(&x);
+        let status = solve(&problem, &dense, &params, None);
+        assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+        check_optimal(&problem, &status, 1e-6);
+    }
+
+    #[test]
+    fn optimal_for_regression() {
+        let (x, y) = regression(40);
+        let params = Params::new().with_tol(1e-8).with_max_steps(100000);
+        let problem = Regression::new(&y, ProblemParams::new().with_lambda(0.1)).with_epsilon(0.1);
+        let dense = rows(&x);
+        let status = solve(&problem, &dense, &params, None);
+        assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+        check_optimal(&problem, &status, 1e-6);
+        // the two variables of a sample are never both nonzero
+        for i in 0..y.len() {
+            assert!(status.a[i] == 0.0 || status.a[i + y.len()] == 0.0);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "problem and data should not be empty")]
+    fn empty_data() {
+        let (_x, y) = classification(10);
+        let problem = Classification::new(&y, ProblemParams::new());
+        let data: Vec<&[f64]> = Vec::new();
+        solve(&problem, &data, &Params::new(), None);
+    }
+}
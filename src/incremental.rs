@@ -0,0 +1,464 @@
+//! Incremental learning and unlearning of single variables (Cauwenberghs and Poggio, 2001)
+//!
+//! For a [`DualProblem`] with quadratic dual loss functions, the optimality conditions stay satisfied if a single coefficient `a_c`
+//! is changed and the coefficients strictly between their bounds (margin set) as well as the offset `b` follow linearly.
+//! This only holds until another coefficient reaches a bound or the gradient `gᵢ + b` of a coefficient at a bound vanishes,
+//! in which case the margin set changes and the next linear piece starts.
+//! Moving from event to event updates an already solved [`Status`] without retraining from scratch.
+use crate::kernel::{compute_rows, Kernel};
+use crate::problem::DualProblem;
+use crate::sensitivity::ReducedSystem;
+use crate::status::{Status, StatusCode};
+use crate::time::{now, until_now};
+
+/// Parameters of the incremental updates
+#[derive(Clone, Debug)]
+pub struct Params {
+    /// Maximum number of events (changes of the margin set)
+    pub max_steps: usize,
+    /// Tolerance for the optimality condition of the changed coefficient
+    pub tol: f64,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            max_steps: 100000,
+            tol: 1e-10,
+        }
+    }
+
+    /// Updates the maximum number of events.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Updates the tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Goal of moving the coefficient
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    /// Satisfy the optimality condition of the coefficient.
+    Learn,
+    /// Make the coefficient vanish.
+    Unlearn,
+}
+
+/// Event ending a linear piece
+enum Event {
+    /// The moved coefficient reaches its goal or one of its bounds.
+    Done,
+    /// The coefficient at the given position in the margin set reaches the given bound.
+    Leave(usize, f64),
+    /// The gradient of the coefficient at a bound vanishes.
+    Enter(usize),
+}
+
+/// Computes the ith row of the kernel matrix with respect to all variables.
+fn kernel_row(kernel: &dyn Kernel, i: usize, full_set: &[usize]) -> Vec<f64> {
+    let mut kis = vec![vec![0.0; full_set.len()]];
+    compute_rows(kernel, &[i], &mut kis, full_set);
+    kis.pop().unwrap()
+}
+
+/// Moves the cth coefficient while keeping the optimality conditions for the other ones.
+fn adiabatic(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    c: usize,
+    params: &Params,
+    target: Target,
+) -> Status {
+    assert!(problem.is_quad(), "dual loss functions should be quadratic");
+    assert!(
+        !problem.has_max_asum(),
+        "bound on the 1-norm is not supported"
+    );
+    let start = now();
+    let mut status = status;
+    let n = problem.size();
+    let lambda = problem.lambda();
+    let full_set: Vec<usize> = (0..n).collect();
+    for i in 0..n {
+        status.g[i] = status.ka[i] + problem.d_dloss(i, status.a[i]);
+    }
+    let (lb_c, ub_c) = (problem.lb(c), problem.ub(c));
+
+    // margin set with kernel rows
+    let mut margin: Vec<usize> = (0..n)
+        .filter(|&i| i != c && status.a[i] > problem.lb(i) && status.a[i] < problem.ub(i))
+        .collect();
+    let mut kis: Vec<Vec<f64>> = vec![vec![0.0; n]; margin.len()];
+    compute_rows(&*kernel, &margin, &mut kis, &full_set);
+    let kc = kernel_row(&*kernel, c, &full_set);
+
+    let mut step: usize = 0;
+    status.opt_status.code = loop {
+        if step >= params.max_steps {
+            break StatusCode::MaxSteps;
+        }
+
+        // direction of the change of the cth coefficient
+        let ac = status.a[c];
+        let rc = status.g[c] + status.b;
+        let dir = match target {
+            Target::Learn if rc < -params.tol && ac < ub_c => 1.0,
+            Target::Learn if rc > params.tol && ac > lb_c => -1.0,
+            Target::Unlearn if ac != 0.0 => -ac.signum(),
+            _ => break StatusCode::Optimal,
+        };
+
+        // rates of change of the coefficients and the offset
+        let (da_c, da_margin, db) = if margin.is_empty() {
+            // only the offset can move until a coefficient enters the margin set
+            (0.0, Vec::new(), dir)
+        } else {
+            let diag: Vec<f64> = margin
+                .iter()
+                .map(|&i| problem.d2_dloss(i, status.a[i]))
+                .collect();
+            let rows: Vec<&[f64]> = kis.iter().map(|ki| ki.as_slice()).collect();
+            let rhs: Vec<f64> = margin.iter().map(|&i| -kc[i] / lambda).collect();
+            let Some((beta, beta_b)) = ReducedSystem::new(&margin, &rows, &diag, lambda)
+                .and_then(|system| system.solve_with_sum(&rhs, -1.0))
+            else {
+                break StatusCode::NoStepPossible;
+            };
+            let da_margin: Vec<f64> = beta.iter().map(|beta_i| dir * beta_i).collect();
+            (dir, da_margin, dir * beta_b)
+        };
+        let mut dka: Vec<f64> = kc.iter().map(|kck| da_c * kck / lambda).collect();
+        for (ki, dai) in kis.iter().zip(da_margin.iter()) {
+            for (dkak, kik) in dka.iter_mut().zip(ki.iter()) {
+                *dkak += dai * kik / lambda;
+            }
+        }
+
+        // find the next event
+        let mut next = (f64::INFINITY, Event::Done);
+        let drc = dka[c] + db + problem.d2_dloss(c, ac) * da_c;
+        if target == Target::Learn && rc * drc < 0.0 {
+            next = (-rc / drc, Event::Done);
+        }
+        let bound_c = match target {
+            Target::Learn if da_c > 0.0 => ub_c,
+            Target::Learn if da_c < 0.0 => lb_c,
+            Target::Unlearn if da_c != 0.0 => 0.0,
+            _ => ac,
+        };
+        if bound_c != ac && (bound_c - ac) / da_c < next.0 {
+            next = ((bound_c - ac) / da_c, Event::Done);
+        }
+        for (idx_i, (&i, &dai)) in margin.iter().zip(da_margin.iter()).enumerate() {
+            let bound = if dai > 0.0 {
+                problem.ub(i)
+            } else if dai < 0.0 {
+                problem.lb(i)
+            } else {
+                continue;
+            };
+            let si = f64::max((bound - status.a[i]) / dai, 0.0);
+            if si < next.0 {
+                next = (si, Event::Leave(idx_i, bound));
+            }
+        }
+        let mut in_margin = vec![false; n];
+        margin.iter().for_each(|&i| in_margin[i] = true);
+        for i in 0..n {
+            if i == c || in_margin[i] {
+                continue;
+            }
+            let ri = status.g[i] + status.b;
+            let dri = dka[i] + db;
+            // coefficients at the lower (upper) bound have nonnegative (nonpositive) gradients
+            let si = if status.a[i] <= problem.lb(i) && dri < 0.0 {
+                f64::max(ri, 0.0) / -dri
+            } else if status.a[i] >= problem.ub(i) && dri > 0.0 {
+                f64::max(-ri, 0.0) / dri
+            } else {
+                continue;
+            };
+            if si < next.0 {
+                next = (si, Event::Enter(i));
+            }
+        }
+        let (s, event) = next;
+        if !s.is_finite() {
+            break StatusCode::NoStepPossible;
+        }
+
+        // move to the event
+        status.a[c] += s * da_c;
+        for (&i, dai) in margin.iter().zip(da_margin.iter()) {
+            status.a[i] += s * dai;
+        }
+        status.b += s * db;
+        for (kak, dkak) in status.ka.iter_mut().zip(dka.iter()) {
+            *kak += s * dkak;
+        }
+        step += 1;
+        match event {
+            Event::Done => {
+                if bound_c != ac && s == (bound_c - ac) / da_c {
+                    status.a[c] = bound_c;
+                }
+            }
+            Event::Leave(idx_i, bound) => {
+                status.a[margin[idx_i]] = bound;
+                margin.swap_remove(idx_i);
+                kis.swap_remove(idx_i);
+            }
+            Event::Enter(i) => {
+                margin.push(i);
+                kis.push(kernel_row(&*kernel, i, &full_set));
+            }
+        }
+        for i in 0..n {
+            status.g[i] = status.ka[i] + problem.d_dloss(i, status.a[i]);
+        }
+        if matches!(event, Event::Done) {
+            break StatusCode::Optimal;
+        }
+    };
+
+    // compute the remaining parts of the status
+    let mut g_max = f64::NEG_INFINITY;
+    let mut g_min = f64::INFINITY;
+    status.asum = 0.0;
+    for i in 0..n {
+        if status.a[i] > problem.lb(i) {
+            g_max = f64::max(g_max, status.g[i]);
+        }
+        if status.a[i] < problem.ub(i) {
+            g_min = f64::min(g_min, status.g[i]);
+        }
+        status.asum += problem.sign(i) * status.a[i];
+    }
+    status.opt_status.violation = f64::max(g_max - g_min, 0.0);
+    status.value = -problem.objective(&status);
+    status.opt_status.steps = step;
+    status.opt_status.time = until_now(start);
+    status
+}
+
+/// Changes the cth coefficient of an optimal [`Status`] until it satisfies its optimality condition,
+/// while the optimality conditions of the other coefficients are kept.
+///
+/// This incorporates a variable whose loss function has been added or changed, e.g., for a new sample with coefficient zero (see [`add_sample`]).
+/// For problems with several variables per sample (like [`Regression`](crate::problem::Regression)), all of them have to be learned.
+pub fn learn(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    c: usize,
+    params: &Params,
+) -> Status {
+    adiabatic(problem, kernel, status, c, params, Target::Learn)
+}
+
+/// Changes the cth coefficient of an optimal [`Status`] to zero, while the optimality conditions of the other coefficients are kept.
+///
+/// Afterwards, the variable has no influence on the decision function anymore and can be removed (see [`remove_sample`]).
+pub fn unlearn(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    c: usize,
+    params: &Params,
+) -> Status {
+    adiabatic(problem, kernel, status, c, params, Target::Unlearn)
+}
+
+/// Returns the positions of the variables of the ith sample for a problem with `n` variables and a kernel with `m` samples.
+///
+/// Problems with several variables per sample (like [`Regression`](crate::problem::Regression)) store them in blocks of size `m`.
+fn sample_variables(n: usize, m: usize, i: usize) -> Vec<usize> {
+    assert!(
+        m > 0 && n % m == 0,
+        "problem should have the same number of variables for each sample of the kernel"
+    );
+    (0..n / m).map(|copy| copy * m + i).collect()
+}
+
+/// Moves the coefficients `cs` one after another (see [`adiabatic`]) until one of them fails.
+fn adiabatic_all(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    cs: &[usize],
+    params: &Params,
+    target: Target,
+) -> Status {
+    let start = now();
+    let mut status = status;
+    let mut steps = 0;
+    for &c in cs.iter() {
+        status = adiabatic(problem, kernel, status, c, params, target);
+        steps += status.opt_status.steps;
+        if !matches!(status.opt_status.code, StatusCode::Optimal) {
+            break;
+        }
+    }
+    status.opt_status.steps = steps;
+    status.opt_status.time = until_now(start);
+    status
+}
+
+/// Extends an optimal [`Status`] by the variables of the last sample and learns them (see [`learn`]).
+///
+/// The problem and the kernel should already contain the new sample as their last one, whereas the status belongs to the previous samples.
+/// For problems with several variables per sample (like [`Regression`](crate::problem::Regression)),
+/// the variables of the new sample are inserted at the end of each block.
+pub fn add_sample(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    params: &Params,
+) -> Status {
+    let n = problem.size();
+    let m = kernel.size();
+    let cs = sample_variables(n, m, m - 1);
+    assert_eq!(
+        status.a.len() + cs.len(),
+        n,
+        "problem should have exactly one additional sample"
+    );
+    let mut status = status;
+    for &c in cs.iter() {
+        status.a.insert(c, 0.0);
+        status.ka.insert(c, 0.0);
+        status.g.insert(c, 0.0);
+    }
+    let full_set: Vec<usize> = (0..n).collect();
+    let kc = kernel_row(&*kernel, m - 1, &full_set);
+    let kac = status
+        .a
+        .iter()
+        .zip(kc.iter())
+        .map(|(aj, kcj)| aj * kcj)
+        .sum::<f64>()
+        / problem.lambda();
+    for &c in cs.iter() {
+        status.ka[c] = kac;
+    }
+    adiabatic_all(problem, kernel, status, &cs, params, Target::Learn)
+}
+
+/// Unlearns the variables of the cth sample of an optimal [`Status`] (see [`unlearn`]) and removes them from the status.
+///
+/// The variables are only removed if the result is optimal (see [`Status::opt_status`]).
+/// Afterwards, the sample should be removed from the problem and the kernel as well.
+pub fn remove_sample(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    status: Status,
+    c: usize,
+    params: &Params,
+) -> Status {
+    let cs = sample_variables(problem.size(), kernel.size(), c);
+    let mut status = adiabatic_all(problem, kernel, status, &cs, params, Target::Unlearn);
+    if !matches!(status.opt_status.code, StatusCode::Optimal) {
+        return status;
+    }
+    for &c in cs.iter().rev() {
+        status.a.remove(c);
+        status.ka.remove(c);
+        status.g.remove(c);
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{Classification, Params as ProblemParams, Regression, LSSVM};
+    use crate::smo;
+    use crate::testing::{classification, regression, rows};
+
+    /// Adds the last sample to the SMO solution of the other ones, removes the sample `c` afterwards
+    /// and compares both results with the SMO solutions of the respective samples.
+    fn check_add_remove(
+        new_problem: &dyn Fn(&[f64]) -> Box<dyn DualProblem + '_>,
+        y: &[f64],
+        x: &[Vec<f64>],
+        c: usize,
+    ) {
+        let smo_params = smo::Params::new().with_tol(1e-10);
+        let params = Params::new();
+        let check = |status: &Status, y: Vec<f64>, x: Vec<&[f64]>| {
+            let problem = new_problem(&y);
+            let mut kernel = gaussian::from_vecs(x, 1.0);
+            let reference = smo::solve(problem.as_ref(), &mut kernel, &smo_params, None);
+            assert_eq!(status.a.len(), reference.a.len());
+            let value = -problem.objective(status);
+            assert!((value - reference.value).abs() < 1e-6 * (1.0 + reference.value.abs()));
+        };
+        let rows = rows(x);
+        let m = rows.len();
+
+        let problem = new_problem(&y[..m - 1]);
+        let mut kernel = gaussian::from_vecs(rows[..m - 1].to_vec(), 1.0);
+        let status = smo::solve(problem.as_ref(), &mut kernel, &smo_params, None);
+
+        let problem = new_problem(y);
+        let mut kernel = gaussian::from_vecs(rows.clone(), 1.0);
+        let status = add_sample(problem.as_ref(), &mut kernel, status, &params);
+        assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+        check(&status, y.to_vec(), rows.clone());
+
+        let cs = sample_variables(problem.size(), m, c);
+        assert!(cs.iter().any(|&i| status.a[i] != 0.0));
+        let status = remove_sample(problem.as_ref(), &mut kernel, status, c, &params);
+        assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+        let mut y_removed = y.to_vec();
+        let mut rows_removed = rows;
+        y_removed.remove(c);
+        rows_removed.remove(c);
+        check(&status, y_removed, rows_removed);
+    }
+
+    #[test]
+    fn classification_add_and_remove_sample() {
+        let (x, y) = classification(20);
+        fn new_problem(y: &[f64]) -> Box<dyn DualProblem + '_> {
+            Box::new(Classification::new(
+                y,
+                ProblemParams::new().with_lambda(0.1),
+            ))
+        }
+        check_add_remove(&new_problem, &y, &x, 3);
+    }
+
+    #[test]
+    fn lssvm_add_and_remove_sample() {
+        let (x, y) = regression(20);
+        fn new_problem(y: &[f64]) -> Box<dyn DualProblem + '_> {
+            Box::new(LSSVM::new(y, ProblemParams::new().with_lambda(0.1)))
+        }
+        check_add_remove(&new_problem, &y, &x, 3);
+    }
+
+    #[test]
+    fn regression_add_and_remove_sample() {
+        let (x, y) = regression(20);
+        fn new_problem(y: &[f64]) -> Box<dyn DualProblem + '_> {
+            Box::new(Regression::new(y, ProblemParams::new().with_lambda(0.1)).with_epsilon(0.1))
+        }
+        check_add_remove(&new_problem, &y, &x, 3);
+    }
+}
@@ -1,14 +1,30 @@
 //! Kernels
 
 mod cached;
-pub use cached::{cache, CachedKernel};
+pub use cached::{cache, cache_megabytes, CacheStats, CachedKernel};
+mod centered;
+pub use centered::CenteredKernel;
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "mmap")]
+pub use mapped::MappedKernel;
+mod lowrank;
+pub use lowrank::{Factor, LowRankKernel};
+mod normalized;
+pub use normalized::NormalizedKernel;
 mod precomputed;
-pub use precomputed::PrecomputedKernel;
+pub use precomputed::{PrecomputedEntry, PrecomputedKernel};
 mod row;
 pub use row::{KernelFunction, RowKernel};
 
+use crate::problem::base::MaybeSync;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
 /// An abstract kernel matrix
-pub trait Kernel {
+///
+/// With the `parallel` feature, kernel matrices have to be [`Sync`] such that rows can be computed by several threads.
+pub trait Kernel: MaybeSync {
     /// Computes the ith row of the kernel matrix with entries according to `active_set` and saves it into the (preallocated) slice `ki`.
     fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]);
 
@@ -23,15 +39,15 @@ pub trait Kernel {
     /// Expands (unshrinks) the current active set.
     fn set_active(&mut self, _old: &Vec<usize>, _new: &Vec<usize>) {}
 
+    /// Returns a factor `G` with `K = G Gᵀ` if the kernel matrix is given in low-rank form.
+    fn factor(&self) -> Option<Factor<'_>> {
+        None
+    }
+
     /// Computes a set of rows of the kernel matrix and hands them over to the callback `fun`.
     fn use_rows(&mut self, idxs: &[usize], active_set: &[usize], fun: &mut dyn FnMut(Vec<&[f64]>)) {
-        let mut kidxs = Vec::with_capacity(idxs.len());
-        let active_size = active_set.len();
-        for &idx in idxs.iter() {
-            let mut kidx = vec![0.0; active_size];
-            self.compute_row(idx, &mut kidx, active_set);
-            kidxs.push(kidx);
-        }
+        let mut kidxs = vec![vec![0.0; active_set.len()]; idxs.len()];
+        compute_rows(self, idxs, &mut kidxs, active_set);
         fun(kidxs.iter().map(|ki| ki.as_slice()).collect());
     }
 }
@@ -45,4 +61,80 @@ pub trait Deriv {
     fn num_params(&self) -> usize;
 }
 
+/// Minimum number of entries of a row (or of active variables in the solvers) processed by a single thread (if the `parallel` feature is enabled)
+pub const ROW_CHUNK_SIZE: usize = 1024;
+
+/// Splits the computation of a row with respect to `active_set` into chunks, which are computed in parallel if the `parallel` feature is enabled.
+pub(crate) fn compute_chunked<F>(ki: &mut [f64], active_set: &[usize], fun: &F)
+where
+    F: Fn(&mut [f64], &[usize]) + MaybeSync + ?Sized,
+{
+    #[cfg(feature = "parallel")]
+    if active_set.len() > ROW_CHUNK_SIZE {
+        ki.par_chunks_mut(ROW_CHUNK_SIZE)
+            .zip(active_set.par_chunks(ROW_CHUNK_SIZE))
+            .for_each(|(ki_chunk, active_chunk)| fun(ki_chunk, active_chunk));
+        return;
+    }
+    fun(ki, active_set)
+}
+
+/// Computes the rows `idxs` of the kernel matrix into `kis`, concurrently if the `parallel` feature is enabled.
+pub(crate) fn compute_rows<K: Kernel + ?Sized>(
+    kernel: &K,
+    idxs: &[usize],
+    kis: &mut [Vec<f64>],
+    active_set: &[usize],
+) {
+    #[cfg(feature = "parallel")]
+    kis.par_iter_mut()
+        .zip(idxs.par_iter())
+        .for_each(|(ki, &idx)| kernel.compute_row(idx, ki, active_set));
+    #[cfg(not(feature = "parallel"))]
+    for (ki, &idx) in kis.iter_mut().zip(idxs.iter()) {
+        kernel.compute_row(idx, ki, active_set);
+    }
+}
+
+pub mod diagnostics;
+pub mod fourier;
+pub use fourier::RandomFourierFeatures;
 pub mod gaussian;
+pub use gaussian::GaussianKernel;
+pub mod graph;
+pub mod histogram;
+pub mod linear;
+pub mod nystrom;
+pub use nystrom::Nystrom;
+pub mod sparse;
+pub mod string;
+pub mod tanimoto;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{polynomial, polynomial_kernel, samples};
+
+    #[test]
+    fn chunked_row_agrees_with_sequential() {
+        let n = 2 * ROW_CHUNK_SIZE + 17;
+        let x = samples(n);
+        let kernel = polynomial_kernel(&x);
+        let active_set: Vec<usize> = (0..n).map(|j| (11 * j) % n).collect();
+        let mut ki = vec![0.0; n];
+        kernel.compute_row(5, &mut ki, &active_set);
+        for (kij, &j) in ki.iter().zip(active_set.iter()) {
+            assert_eq!(*kij, polynomial(&x[5], &x[j]));
+        }
+
+        let mut positions = vec![0.0; n];
+        compute_chunked(&mut positions, &active_set, &|chunk, active_chunk| {
+            for (pj, &j) in chunk.iter_mut().zip(active_chunk.iter()) {
+                *pj = j as f64;
+            }
+        });
+        for (pj, &j) in positions.iter().zip(active_set.iter()) {
+            assert_eq!(*pj, j as f64);
+        }
+    }
+}
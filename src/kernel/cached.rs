@@ -1,22 +1,107 @@
-use super::Kernel;
+use super::{compute_rows, Factor, Kernel};
 use caches::{Cache, RawLRU};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::sync::Arc;
+
+/// Statistics of the accesses to the rows of a [`CachedKernel`]
+#[derive(Clone, Debug, Default)]
+pub struct CacheStats {
+    /// Number of requested rows found in the cache
+    pub hits: usize,
+    /// Number of requested rows found in the cache but extended by newly active entries
+    pub partial_hits: usize,
+    /// Number of requested rows computed from scratch
+    pub misses: usize,
+    /// Number of rows removed from the cache
+    pub evictions: usize,
+}
+
+struct CachedRow {
+    /// Active set the values correspond to
+    active_set: Arc<Vec<usize>>,
+    values: Vec<f64>,
+}
+
+struct Job {
+    idx: usize,
+    active_set: Vec<usize>,
+    values: Vec<f64>,
+}
 
 /// A struct to cache rows of a kernel matrix.
+///
+/// The size of the cache is limited by a budget of bytes.
+/// Cached rows are kept when the active set changes and only the newly active entries are computed on their next use.
 pub struct CachedKernel<'a> {
-    cache: RawLRU<usize, usize>,
-    data: Vec<Vec<f64>>,
+    cache: RawLRU<usize, CachedRow>,
+    budget: usize,
+    /// Number of rows the budget is adapted to (if given in rows instead of bytes)
+    capacity: Option<usize>,
+    used: usize,
+    active_set: Arc<Vec<usize>>,
+    stats: CacheStats,
     base: Box<dyn Kernel + 'a>,
 }
 
+const BYTES_PER_MEGABYTE: f64 = 1024.0 * 1024.0;
+
 impl<'a> CachedKernel<'a> {
-    /// Generates a cached version of the given kernel matrix.
+    /// Generates a cached version of the given kernel matrix with space for `capacity` (full) rows.
+    ///
+    /// The length of a full row is the size of the training problem (e.g., twice the number of samples for regression),
+    /// which is determined by the largest active set requested so far.
     pub fn from(base: Box<dyn Kernel + 'a>, capacity: usize) -> CachedKernel<'a> {
+        let budget = capacity * bytes(base.size());
+        let mut kernel = Self::with_budget(base, budget);
+        kernel.capacity = Some(capacity);
+        kernel
+    }
+
+    /// Generates a cached version of the given kernel matrix using at most `megabytes` MB of memory (comparable to option `-m` of LIBSVM).
+    pub fn with_megabytes(base: Box<dyn Kernel + 'a>, megabytes: f64) -> CachedKernel<'a> {
+        Self::with_budget(base, (megabytes * BYTES_PER_MEGABYTE) as usize)
+    }
+
+    fn with_budget(base: Box<dyn Kernel + 'a>, budget: usize) -> CachedKernel<'a> {
+        let max_rows = usize::max(2 * base.size(), 1);
         CachedKernel {
-            cache: RawLRU::new(capacity).unwrap(),
-            data: Vec::new(),
+            cache: RawLRU::new(max_rows).unwrap(),
+            budget,
+            capacity: None,
+            used: 0,
+            active_set: Arc::new(Vec::new()),
+            stats: CacheStats::default(),
             base,
         }
     }
+
+    /// Returns the statistics of the accesses to the cache.
+    pub fn stats(&self) -> &CacheStats {
+        &self.stats
+    }
+
+    /// Resets the statistics of the accesses to the cache.
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Returns the number of bytes currently used for cached rows.
+    pub fn used_bytes(&self) -> usize {
+        self.used
+    }
+
+    fn evict_until(&mut self, required: usize) {
+        while self.used + required > self.budget {
+            match self.cache.remove_lru() {
+                Some((_idx, row)) => {
+                    self.used -= bytes(row.values.len());
+                    self.stats.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
 }
 
 /// Add cache to base kernel
@@ -28,22 +113,46 @@ pub fn cache<'a>(base: Box<dyn Kernel + 'a>, cache_size: usize) -> Box<dyn Kerne
     }
 }
 
-fn find_common<T>(a: &Vec<T>, b: &Vec<T>) -> Vec<usize>
-where
-    T: PartialEq,
-{
-    let mut res = Vec::new();
+/// Add cache with a size given in megabytes to base kernel
+pub fn cache_megabytes<'a>(base: Box<dyn Kernel + 'a>, megabytes: f64) -> Box<dyn Kernel + 'a> {
+    if megabytes > 0.0 {
+        Box::new(CachedKernel::with_megabytes(base, megabytes))
+    } else {
+        base
+    }
+}
+
+fn bytes(len: usize) -> usize {
+    len * std::mem::size_of::<f64>()
+}
+
+/// Splits the (sorted) active set `new` into the positions of entries available in `old` and the missing entries.
+fn align(old: &[usize], new: &[usize]) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut positions = Vec::with_capacity(new.len());
+    let mut missing = Vec::new();
     let mut it: usize = 0;
-    for (idx, i) in a.iter().enumerate() {
-        if i == &b[it] {
+    for &k in new.iter() {
+        while it < old.len() && old[it] < k {
             it += 1;
-            res.push(idx);
-            if it >= b.len() {
-                break;
-            }
+        }
+        if it < old.len() && old[it] == k {
+            positions.push(Some(it));
+        } else {
+            positions.push(None);
+            missing.push(k);
         }
     }
-    res
+    (positions, missing)
+}
+
+fn compute_jobs(base: &dyn Kernel, jobs: &mut [Job]) {
+    #[cfg(feature = "parallel")]
+    jobs.par_iter_mut()
+        .for_each(|job| base.compute_row(job.idx, &mut job.values, &job.active_set));
+    #[cfg(not(feature = "parallel"))]
+    for job in jobs.iter_mut() {
+        base.compute_row(job.idx, &mut job.values, &job.active_set);
+    }
 }
 
 impl<'a> super::Kernel for CachedKernel<'a> {
@@ -56,47 +165,158 @@ impl<'a> super::Kernel for CachedKernel<'a> {
     }
 
     fn use_rows(&mut self, idxs: &[usize], active_set: &[usize], fun: &mut dyn FnMut(Vec<&[f64]>)) {
-        assert!(idxs.len() <= self.cache.cap(), "cache size too small");
-        let poss: Vec<_> = idxs
-            .iter()
-            .map(|&idx| match self.cache.get(&idx) {
-                Some(&pos) => pos,
-                None => {
-                    let size = self.cache.cap();
-                    let pos = if self.data.len() < size {
-                        let ki: Vec<f64> = vec![0.0; active_set.len()];
-                        let pos = self.data.len();
-                        self.data.push(ki);
-                        pos
+        if let Some(capacity) = self.capacity {
+            self.budget = usize::max(self.budget, capacity * bytes(active_set.len()));
+        }
+        if idxs.len() * bytes(active_set.len()) > self.budget {
+            // the requested rows do not fit into the cache at once
+            self.stats.misses += idxs.len();
+            let mut kidxs = vec![vec![0.0; active_set.len()]; idxs.len()];
+            compute_rows(self.base.as_ref(), idxs, &mut kidxs, active_set);
+            fun(kidxs.iter().map(|ki| ki.as_slice()).collect());
+            return;
+        }
+        if self.active_set.as_slice() != active_set {
+            self.active_set = Arc::new(active_set.to_vec());
+        }
+
+        // collect cached entries and determine entries to be computed
+        let mut jobs = Vec::new();
+        let mut partial = Vec::new();
+        for &idx in idxs.iter() {
+            if jobs.iter().any(|job: &Job| job.idx == idx) {
+                continue;
+            }
+            let row = match self.cache.get(&idx) {
+                Some(row) => {
+                    if Arc::ptr_eq(&row.active_set, &self.active_set) {
+                        self.stats.hits += 1;
+                        continue;
+                    }
+                    self.cache.remove(&idx)
+                }
+                None => None,
+            };
+            match row {
+                Some(row) => {
+                    self.used -= bytes(row.values.len());
+                    let (positions, missing) = align(&row.active_set, active_set);
+                    if missing.is_empty() {
+                        self.stats.hits += 1;
                     } else {
-                        let (_idx, pos) = self.cache.remove_lru().unwrap();
-                        pos
-                    };
-                    self.base.compute_row(idx, &mut self.data[pos], active_set);
-                    self.cache.put(idx, pos);
-                    pos
+                        self.stats.partial_hits += 1;
+                    }
+                    let values = vec![0.0; missing.len()];
+                    partial.push((jobs.len(), positions, row.values));
+                    jobs.push(Job {
+                        idx,
+                        active_set: missing,
+                        values,
+                    });
+                }
+                None => {
+                    self.stats.misses += 1;
+                    jobs.push(Job {
+                        idx,
+                        active_set: active_set.to_vec(),
+                        values: vec![0.0; active_set.len()],
+                    });
                 }
-            })
-            .collect();
-        fun(poss
-            .into_iter()
-            .map(|pos| self.data[pos].as_slice())
+            }
+            // reserve space for the new row (the requested rows are the most recently used ones)
+            self.evict_until(bytes(active_set.len()));
+            self.used += bytes(active_set.len());
+        }
+
+        // compute missing entries (concurrently if possible)
+        compute_jobs(self.base.as_ref(), &mut jobs);
+
+        // merge partially cached rows with new entries
+        for (idx_job, positions, old_values) in partial.into_iter() {
+            let job = &mut jobs[idx_job];
+            let mut new_values = job.values.iter();
+            job.values = positions
+                .iter()
+                .map(|pos| match pos {
+                    Some(pos) => old_values[*pos],
+                    None => *new_values.next().unwrap(),
+                })
+                .collect();
+        }
+        for job in jobs.into_iter() {
+            self.cache.put(
+                job.idx,
+                CachedRow {
+                    active_set: self.active_set.clone(),
+                    values: job.values,
+                },
+            );
+        }
+
+        fun(idxs
+            .iter()
+            .map(|idx| self.cache.peek(idx).unwrap().values.as_slice())
             .collect());
     }
 
-    fn restrict_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
-        let sub = find_common(old, new);
-        for ki in self.data.iter_mut() {
-            *ki = sub.iter().map(|&idx| ki[idx]).collect();
-        }
+    fn restrict_active(&mut self, _old: &Vec<usize>, new: &Vec<usize>) {
+        self.active_set = Arc::new(new.clone());
     }
 
-    fn set_active(&mut self, _old: &Vec<usize>, _new: &Vec<usize>) {
-        self.cache = RawLRU::new(self.cache.cap()).unwrap();
-        self.data = Vec::new();
+    fn set_active(&mut self, _old: &Vec<usize>, new: &Vec<usize>) {
+        self.active_set = Arc::new(new.clone());
     }
 
     fn diag(&self, i: usize) -> f64 {
         self.base.diag(i)
     }
+
+    fn factor(&self) -> Option<Factor<'_>> {
+        self.base.factor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{Params, Regression};
+    use crate::smo;
+    use crate::testing::{regression, rows};
+
+    #[test]
+    fn regression_with_small_capacity() {
+        let (x, y) = regression(20);
+        let problem = Regression::new(&y, Params::new().with_lambda(0.1)).with_epsilon(0.05);
+        let params = smo::Params::new().with_tol(1e-6);
+        let rows = rows(&x);
+        let mut base = gaussian::from_vecs(rows.clone(), 1.0);
+        let reference = smo::solve(&problem, &mut base, &params, None);
+        for capacity in [1, 2, 3, 8] {
+            let mut kernel =
+                CachedKernel::from(Box::new(gaussian::from_vecs(rows.clone(), 1.0)), capacity);
+            let status = smo::solve(&problem, &mut kernel, &params, None);
+            assert!(kernel.used_bytes() <= capacity * bytes(2 * y.len()));
+            for (ai, ri) in status.a.iter().zip(reference.a.iter()) {
+                assert!((ai - ri).abs() < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn partial_hits_after_shrinking() {
+        let (x, _y) = regression(20);
+        let rows = rows(&x);
+        let mut kernel = CachedKernel::from(Box::new(gaussian::from_vecs(rows, 1.0)), 4);
+        let full: Vec<usize> = (0..20).collect();
+        let part: Vec<usize> = (0..20).step_by(2).collect();
+        let mut values = Vec::new();
+        kernel.use_rows(&[3], &part, &mut |kis| values = kis[0].to_vec());
+        kernel.use_rows(&[3], &full, &mut |kis| values = kis[0].to_vec());
+        assert_eq!(kernel.stats().misses, 1);
+        assert_eq!(kernel.stats().partial_hits, 1);
+        let mut expected = vec![0.0; 20];
+        kernel.compute_row(3, &mut expected, &full);
+        assert_eq!(values, expected);
+    }
 }
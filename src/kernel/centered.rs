@@ -0,0 +1,180 @@
+use super::{compute_rows, Kernel, KernelFunction};
+use crate::{predict, Status};
+
+/// Number of rows computed at once while computing the row means
+const ROWS_PER_BLOCK: usize = 64;
+
+/// A kernel matrix of a base kernel matrix after centering the samples in feature space
+///
+/// The entries are `K_ij - m_i - m_j + M` with row means `m_i` and total mean `M` of the base kernel matrix,
+/// which are computed once when creating the kernel matrix.
+pub struct CenteredKernel<'a> {
+    base: Box<dyn Kernel + 'a>,
+    means: Vec<f64>,
+    total_mean: f64,
+}
+
+impl<'a> CenteredKernel<'a> {
+    /// Creates a [`CenteredKernel`] (computing all rows of the base kernel matrix once).
+    pub fn new(base: Box<dyn Kernel + 'a>) -> Self {
+        let n = base.size();
+        let full_set: Vec<usize> = (0..n).collect();
+        let mut means = Vec::with_capacity(n);
+        let mut kis = vec![vec![0.0; n]; usize::min(ROWS_PER_BLOCK, n)];
+        for idxs in full_set.chunks(ROWS_PER_BLOCK) {
+            compute_rows(base.as_ref(), idxs, &mut kis[..idxs.len()], &full_set);
+            means.extend(
+                kis[..idxs.len()]
+                    .iter()
+                    .map(|ki| ki.iter().sum::<f64>() / n as f64),
+            );
+        }
+        let total_mean = means.iter().sum::<f64>() / usize::max(n, 1) as f64;
+        CenteredKernel {
+            base,
+            means,
+            total_mean,
+        }
+    }
+
+    /// Returns the row means of the base kernel matrix.
+    pub fn means(&self) -> &[f64] {
+        &self.means
+    }
+
+    /// Evaluates the decision function for a new sample consistently with the centered kernel matrix.
+    ///
+    /// The kernel function has to be the one of the base kernel matrix and `data` has to contain all training samples.
+    pub fn predict<T>(
+        &self,
+        elem: &T,
+        data: &[T],
+        status: &Status,
+        lmbda: f64,
+        kernel_function: &KernelFunction<T>,
+    ) -> f64 {
+        assert!(!data.is_empty(), "data should not be empty");
+        let kx: Vec<f64> = data.iter().map(|xi| kernel_function(xi, elem)).collect();
+        let mean_elem = kx.iter().sum::<f64>() / kx.len() as f64;
+        // pairs of base kernel values and means, which determine the centered kernel values
+        let pairs: Vec<(f64, f64)> = kx.into_iter().zip(self.means.iter().copied()).collect();
+        predict(
+            &(0.0, mean_elem),
+            &pairs,
+            status,
+            lmbda,
+            &|&(kxi, mi): &(f64, f64), &(_, mx): &(f64, f64)| kxi - mi - mx + self.total_mean,
+        )
+    }
+
+    /// Centers the ith row of the base kernel matrix with entries according to `active_set`.
+    fn center(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let n = self.means.len();
+        let shift_i = self.total_mean - self.means[i % n];
+        for (kij, &j) in ki.iter_mut().zip(active_set.iter()) {
+            *kij += shift_i - self.means[j % n];
+        }
+    }
+}
+
+impl<'a> Kernel for CenteredKernel<'a> {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        self.base.compute_row(i, ki, active_set);
+        self.center(i, ki, active_set);
+    }
+
+    fn size(&self) -> usize {
+        self.base.size()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        let mi = self.means[i % self.means.len()];
+        self.base.diag(i) - 2.0 * mi + self.total_mean
+    }
+
+    fn restrict_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.restrict_active(old, new);
+    }
+
+    fn set_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.set_active(old, new);
+    }
+
+    fn use_rows(&mut self, idxs: &[usize], active_set: &[usize], fun: &mut dyn FnMut(Vec<&[f64]>)) {
+        // use the rows of the base kernel (e.g., from its cache)
+        let mut kidxs: Vec<Vec<f64>> = Vec::with_capacity(idxs.len());
+        self.base
+            .use_rows(idxs, active_set, &mut |kis: Vec<&[f64]>| {
+                kidxs.extend(kis.into_iter().map(|ki| ki.to_vec()));
+            });
+        for (ki, &i) in kidxs.iter_mut().zip(idxs.iter()) {
+            self.center(i, ki, active_set);
+        }
+        fun(kidxs.iter().map(|ki| ki.as_slice()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::CachedKernel;
+    use crate::testing::{polynomial, polynomial_kernel, samples};
+
+    /// Computes the centered kernel value of `u` and `v` with respect to the samples `x`.
+    fn centered(x: &[Vec<f64>], u: &[f64], v: &[f64]) -> f64 {
+        let mean = |w: &[f64]| x.iter().map(|xi| polynomial(xi, w)).sum::<f64>() / x.len() as f64;
+        let total_mean = x.iter().map(|xi| mean(xi)).sum::<f64>() / x.len() as f64;
+        polynomial(u, v) - mean(u) - mean(v) + total_mean
+    }
+
+    #[test]
+    fn rows_and_predict_agree_with_brute_force() {
+        let x = samples(10);
+        let base = CachedKernel::from(Box::new(polynomial_kernel(&x)), 10);
+        let mut kernel = CenteredKernel::new(Box::new(base));
+        let active_set: Vec<usize> = (0..10).rev().collect();
+        let idxs = [3, 7, 3];
+        for _pass in 0..2 {
+            kernel.use_rows(&idxs, &active_set, &mut |kis| {
+                for (ki, &i) in kis.iter().zip(idxs.iter()) {
+                    for (kij, &j) in ki.iter().zip(active_set.iter()) {
+                        assert!((kij - centered(&x, &x[i], &x[j])).abs() < 1e-12);
+                    }
+                }
+            });
+        }
+        let mut ki = vec![0.0; 10];
+        kernel.compute_row(5, &mut ki, &active_set);
+        for (kij, &j) in ki.iter().zip(active_set.iter()) {
+            assert!((kij - centered(&x, &x[5], &x[j])).abs() < 1e-12);
+            assert!((kernel.diag(j) - centered(&x, &x[j], &x[j])).abs() < 1e-12);
+        }
+
+        let mut status = Status::new(10);
+        status.a = (0..10).map(|i| (i as f64 * 0.9).sin()).collect();
+        status.b = 0.3;
+        let elem = vec![0.2, -0.4];
+        let expected = status.b
+            + (0..10)
+                .map(|i| status.a[i] * centered(&x, &x[i], &elem) / 0.5)
+                .sum::<f64>();
+        let kernel_function: KernelFunction<Vec<f64>> = Box::new(|u, v| polynomial(u, v));
+        let v = kernel.predict(&elem, &x, &status, 0.5, &kernel_function);
+        assert!((v - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "data should not be empty")]
+    fn predict_with_empty_data() {
+        let x = samples(10);
+        let kernel = CenteredKernel::new(Box::new(polynomial_kernel(&x)));
+        let kernel_function: KernelFunction<Vec<f64>> = Box::new(|u, v| polynomial(u, v));
+        kernel.predict(
+            &vec![0.2, -0.4],
+            &[],
+            &Status::new(10),
+            0.5,
+            &kernel_function,
+        );
+    }
+}
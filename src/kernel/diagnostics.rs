@@ -0,0 +1,265 @@
+//! Diagnostics of kernel matrices (symmetry, positive semidefiniteness and consistency of the diagonal)
+//!
+//! Solvers like SMO silently assume a symmetric positive semidefinite kernel matrix.
+//! A custom kernel function violating this assumption typically leads to slow or no convergence.
+use super::{compute_rows, Kernel};
+use crate::linalg::{dot, symmetric_eigen};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Parameters of the kernel matrix diagnostics
+pub struct Params {
+    /// Number of sampled rows (the principal submatrix of these rows is checked)
+    pub num_rows: usize,
+    /// Maximum number of Lanczos steps for the estimation of the extreme eigenvalues
+    pub lanczos_steps: usize,
+    /// Tolerance (relative to the largest diagonal element) for the checks
+    pub tol: f64,
+    /// Random seed for sampling
+    pub seed: u64,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            num_rows: 500,
+            lanczos_steps: 50,
+            tol: 1e-8,
+            seed: 0,
+        }
+    }
+
+    /// Updates the number of sampled rows.
+    pub fn with_num_rows(mut self, num_rows: usize) -> Self {
+        self.num_rows = num_rows;
+        self
+    }
+
+    /// Updates the maximum number of Lanczos steps.
+    pub fn with_lanczos_steps(mut self, lanczos_steps: usize) -> Self {
+        self.lanczos_steps = lanczos_steps;
+        self
+    }
+
+    /// Updates the tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// Updates the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Result of the kernel matrix diagnostics
+///
+/// All values refer to the sampled principal submatrix.
+/// By eigenvalue interlacing, a negative eigenvalue of the submatrix proves that the kernel matrix is not positive semidefinite.
+pub struct Report {
+    /// Indices of the sampled rows
+    pub rows: Vec<usize>,
+    /// Maximum absolute difference `|K_ij - K_ji|`
+    pub max_asymmetry: f64,
+    /// Pair of indices with maximum asymmetry
+    pub worst_pair: Option<(usize, usize)>,
+    /// Maximum absolute difference between the diagonal entries of the rows and [`Kernel::diag`]
+    pub max_diag_error: f64,
+    /// Index with maximum diagonal error
+    pub worst_diag: Option<usize>,
+    /// Smallest diagonal element
+    pub min_diag: f64,
+    /// Largest diagonal element
+    pub max_diag: f64,
+    /// Estimate of the smallest eigenvalue (of the symmetric part)
+    pub min_eigenvalue: f64,
+    /// Estimate of the largest eigenvalue (of the symmetric part)
+    pub max_eigenvalue: f64,
+    /// Number of conducted Lanczos steps
+    pub lanczos_steps: usize,
+    /// Whether the asymmetry is below the tolerance
+    pub symmetric: bool,
+    /// Whether the diagonal error is below the tolerance
+    pub diag_consistent: bool,
+    /// Whether the smallest eigenvalue is above the negative tolerance
+    pub positive_semidefinite: bool,
+}
+
+impl Report {
+    /// Returns the estimated condition number (infinite if the smallest eigenvalue is not positive).
+    pub fn condition_number(&self) -> f64 {
+        if self.min_eigenvalue > 0.0 {
+            self.max_eigenvalue / self.min_eigenvalue
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    /// Returns `true` if all checks passed.
+    pub fn is_valid(&self) -> bool {
+        self.symmetric && self.diag_consistent && self.positive_semidefinite
+    }
+}
+
+/// Estimates the extreme eigenvalues of the symmetric `m × m` matrix `a` (row major) by the Lanczos method with full reorthogonalization.
+fn lanczos(a: &[f64], m: usize, steps: usize, rng: &mut StdRng) -> (f64, f64, usize) {
+    if m == 0 {
+        return (0.0, 0.0, 0);
+    }
+    let mut v: Vec<f64> = (0..m).map(|_| rng.gen::<f64>() - 0.5).collect();
+    let norm = dot(&v, &v).sqrt();
+    v.iter_mut().for_each(|vi| *vi /= norm);
+    let mut basis: Vec<Vec<f64>> = Vec::new();
+    let mut alpha = Vec::new();
+    let mut beta: Vec<f64> = Vec::new();
+    for _step in 0..usize::min(steps, m) {
+        let mut w: Vec<f64> = a.chunks_exact(m).map(|ai| dot(ai, &v)).collect();
+        let alpha_k = dot(&w, &v);
+        alpha.push(alpha_k);
+        basis.push(v);
+        for u in basis.iter() {
+            let c = dot(&w, u);
+            w.iter_mut().zip(u).for_each(|(wi, ui)| *wi -= c * ui);
+        }
+        let beta_k = dot(&w, &w).sqrt();
+        if beta_k <= 1e-12 * alpha_k.abs().max(1.0) {
+            break;
+        }
+        beta.push(beta_k);
+        v = w.into_iter().map(|wi| wi / beta_k).collect();
+    }
+    let k = alpha.len();
+    let mut t = vec![0.0; k * k];
+    for i in 0..k {
+        t[i * k + i] = alpha[i];
+        if i + 1 < k {
+            t[i * k + i + 1] = beta[i];
+            t[(i + 1) * k + i] = beta[i];
+        }
+    }
+    let (eigenvalues, _) = symmetric_eigen(t, k);
+    (eigenvalues[k - 1], eigenvalues[0], k)
+}
+
+/// Checks symmetry, positive semidefiniteness and consistency with [`Kernel::diag`] on a random principal submatrix.
+pub fn diagnose(kernel: &dyn Kernel, params: &Params) -> Report {
+    let n = kernel.size();
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    let mut rows = sample(&mut rng, n, usize::min(params.num_rows, n)).into_vec();
+    rows.sort_unstable();
+    let m = rows.len();
+
+    let mut kis = vec![vec![0.0; m]; m];
+    compute_rows(kernel, &rows, &mut kis, &rows);
+
+    let mut report = Report {
+        rows: Vec::new(),
+        max_asymmetry: 0.0,
+        worst_pair: None,
+        max_diag_error: 0.0,
+        worst_diag: None,
+        min_diag: f64::INFINITY,
+        max_diag: f64::NEG_INFINITY,
+        min_eigenvalue: 0.0,
+        max_eigenvalue: 0.0,
+        lanczos_steps: 0,
+        symmetric: true,
+        diag_consistent: true,
+        positive_semidefinite: true,
+    };
+    let mut sym = vec![0.0; m * m];
+    for idx_i in 0..m {
+        let i = rows[idx_i];
+        let diag_error = (kis[idx_i][idx_i] - kernel.diag(i)).abs();
+        if diag_error > report.max_diag_error {
+            report.max_diag_error = diag_error;
+            report.worst_diag = Some(i);
+        }
+        report.min_diag = f64::min(report.min_diag, kis[idx_i][idx_i]);
+        report.max_diag = f64::max(report.max_diag, kis[idx_i][idx_i]);
+        for idx_j in 0..m {
+            let asymmetry = (kis[idx_i][idx_j] - kis[idx_j][idx_i]).abs();
+            if asymmetry > report.max_asymmetry {
+                report.max_asymmetry = asymmetry;
+                report.worst_pair = Some((i, rows[idx_j]));
+            }
+            sym[idx_i * m + idx_j] = 0.5 * (kis[idx_i][idx_j] + kis[idx_j][idx_i]);
+        }
+    }
+    let (min_eigenvalue, max_eigenvalue, steps) = lanczos(&sym, m, params.lanczos_steps, &mut rng);
+    report.min_eigenvalue = min_eigenvalue;
+    report.max_eigenvalue = max_eigenvalue;
+    report.lanczos_steps = steps;
+
+    let tol = params.tol * f64::max(report.max_diag.abs(), 1.0);
+    report.symmetric = report.max_asymmetry <= tol;
+    report.diag_consistent = report.max_diag_error <= tol;
+    report.positive_semidefinite = report.min_eigenvalue >= -tol;
+    report.rows = rows;
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{gaussian, RowKernel};
+    use crate::testing::{rows, samples};
+
+    fn dist_sqr(x: &[f64], y: &[f64]) -> f64 {
+        x.iter().zip(y).map(|(xk, yk)| (xk - yk).powi(2)).sum()
+    }
+
+    #[test]
+    fn accepts_gaussian_kernel() {
+        let x = samples(30);
+        let kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let report = diagnose(&kernel, &Params::new().with_num_rows(20));
+        assert!(report.is_valid());
+        assert_eq!(report.rows.len(), 20);
+        assert_eq!(report.max_asymmetry, 0.0);
+        assert!(report.min_eigenvalue > -1e-8);
+        assert!(report.max_eigenvalue <= 20.0 + 1e-8);
+    }
+
+    #[test]
+    fn flags_invalid_kernels() {
+        let x = samples(30);
+        // squared distances are symmetric but not positive semidefinite (zero trace)
+        let kernel = RowKernel::new(
+            x.clone(),
+            Box::new(|u: &Vec<f64>, v: &Vec<f64>| dist_sqr(u, v)),
+            Box::new(|_u| 0.0),
+        );
+        let report = diagnose(&kernel, &Params::new());
+        assert!(report.symmetric && report.diag_consistent);
+        assert!(!report.positive_semidefinite);
+        assert!(report.min_eigenvalue < 0.0);
+        assert!(report.condition_number().is_infinite());
+
+        // asymmetric perturbation with an inconsistent diagonal
+        let kernel = RowKernel::new(
+            x.clone(),
+            Box::new(|u: &Vec<f64>, v: &Vec<f64>| (-dist_sqr(u, v)).exp() + 0.1 * (u[0] - v[0])),
+            Box::new(|_u| 2.0),
+        );
+        let report = diagnose(&kernel, &Params::new());
+        assert!(!report.symmetric && !report.diag_consistent);
+        assert!(!report.is_valid());
+        let (i, j) = report.worst_pair.unwrap();
+        assert!((report.max_asymmetry - 0.2 * (x[i][0] - x[j][0]).abs()).abs() < 1e-12);
+        assert!((report.max_diag_error - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,207 @@
+//! Random Fourier features for shift-invariant kernels
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::f64::consts::PI;
+
+/// Shift-invariant kernel functions approximated by random Fourier features
+#[derive(Clone, Debug)]
+pub enum ShiftInvariant {
+    /// Gaussian kernel `exp(-γ ||x - y||²)` (see [`super::gaussian::kernel`])
+    Gaussian(f64),
+    /// Laplacian kernel `exp(-γ ||x - y||₁)`
+    Laplacian(f64),
+    /// Matérn kernel with smoothness `nu` and length scale `length_scale`
+    Matern {
+        /// Smoothness parameter ν
+        nu: f64,
+        /// Length scale ℓ
+        length_scale: f64,
+    },
+}
+
+/// Explicit randomized feature map `z` with `z(x)·z(y) ≈ k(x, y)` for a shift-invariant kernel `k`
+///
+/// The features are `z(x) = sqrt(2/D) cos(Ωx + β)` with random frequencies `Ω` drawn from the spectral density of the kernel and random offsets `β`.
+/// Using the features with a linear kernel (see [`super::linear`]) turns a kernel problem into a linear one.
+pub struct RandomFourierFeatures {
+    dim: usize,
+    num_features: usize,
+    /// Frequencies (row major, `num_features × dim`)
+    omega: Vec<f64>,
+    offset: Vec<f64>,
+}
+
+fn normal(rng: &mut StdRng) -> f64 {
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    (-2.0 * u.ln()).sqrt() * (2.0 * PI * v).cos()
+}
+
+fn cauchy(rng: &mut StdRng) -> f64 {
+    (PI * (rng.gen::<f64>() - 0.5)).tan()
+}
+
+/// Draws from the Gamma distribution with given shape and unit scale (Marsaglia–Tsang method).
+fn gamma(rng: &mut StdRng, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = 1.0 - rng.gen::<f64>();
+        return gamma(rng, shape + 1.0) * u.powf(1.0 / shape);
+    }
+    let d = shape - 1.0 / 3.0;
+    let c = 1.0 / (9.0 * d).sqrt();
+    loop {
+        let x = normal(rng);
+        let v = (1.0 + c * x).powi(3);
+        if v <= 0.0 {
+            continue;
+        }
+        let u: f64 = 1.0 - rng.gen::<f64>();
+        if u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+            return d * v;
+        }
+    }
+}
+
+impl RandomFourierFeatures {
+    /// Draws `num_features` random features for samples of dimension `dim` using the random seed `seed`.
+    pub fn new(kernel: &ShiftInvariant, dim: usize, num_features: usize, seed: u64) -> Self {
+        assert!(dim > 0, "dimension of samples should be positive");
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut omega = Vec::with_capacity(num_features * dim);
+        for _ in 0..num_features {
+            match *kernel {
+                ShiftInvariant::Gaussian(gamma) => {
+                    let scale = (2.0 * gamma).sqrt();
+                    omega.extend((0..dim).map(|_| scale * normal(&mut rng)));
+                }
+                ShiftInvariant::Laplacian(gamma) => {
+                    omega.extend((0..dim).map(|_| gamma * cauchy(&mut rng)));
+                }
+                ShiftInvariant::Matern { nu, length_scale } => {
+                    // multivariate t-distribution with 2ν degrees of freedom
+                    let chi2 = 2.0 * gamma(&mut rng, nu);
+                    let scale = (2.0 * nu / chi2).sqrt() / length_scale;
+                    omega.extend((0..dim).map(|_| scale * normal(&mut rng)));
+                }
+            }
+        }
+        let offset = (0..num_features)
+            .map(|_| 2.0 * PI * rng.gen::<f64>())
+            .collect();
+        RandomFourierFeatures {
+            dim,
+            num_features,
+            omega,
+            offset,
+        }
+    }
+
+    /// Returns the number of features.
+    pub fn num_features(&self) -> usize {
+        self.num_features
+    }
+
+    /// Computes the features of a sample.
+    pub fn transform(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(x.len(), self.dim, "unexpected dimension of sample");
+        let scale = (2.0 / self.num_features as f64).sqrt();
+        self.omega
+            .chunks_exact(self.dim)
+            .zip(self.offset.iter())
+            .map(|(omega_r, beta_r)| {
+                let t: f64 = omega_r.iter().zip(x).map(|(w, xk)| w * xk).sum();
+                scale * (t + beta_r).cos()
+            })
+            .collect()
+    }
+
+    /// Computes the features of several samples.
+    pub fn transform_all(&self, data: &[&[f64]]) -> Vec<Vec<f64>> {
+        data.iter().map(|x| self.transform(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linalg::dot;
+    use crate::testing::samples;
+
+    /// Checks that the features approximate the kernel function on a few samples.
+    fn check_approximation(
+        kernel: ShiftInvariant,
+        kernel_function: impl Fn(&[f64], &[f64]) -> f64,
+    ) {
+        let x = samples(5);
+        let features = RandomFourierFeatures::new(&kernel, 2, 20000, 3);
+        let z: Vec<Vec<f64>> = x.iter().map(|xi| features.transform(xi)).collect();
+        for i in 0..x.len() {
+            for j in 0..x.len() {
+                assert!((dot(&z[i], &z[j]) - kernel_function(&x[i], &x[j])).abs() < 2e-2);
+            }
+        }
+    }
+
+    fn distance(x: &[f64], y: &[f64]) -> f64 {
+        x.iter()
+            .zip(y)
+            .map(|(xk, yk)| (xk - yk).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    #[test]
+    fn features_approximate_kernels() {
+        check_approximation(ShiftInvariant::Gaussian(0.5), |x, y| {
+            (-0.5 * distance(x, y).powi(2)).exp()
+        });
+        check_approximation(ShiftInvariant::Laplacian(0.5), |x, y| {
+            let l1: f64 = x.iter().zip(y).map(|(xk, yk)| (xk - yk).abs()).sum();
+            (-0.5 * l1).exp()
+        });
+        // Matérn kernels with ν = 1/2 and ν = 3/2 have closed forms
+        let length_scale = 1.5;
+        check_approximation(
+            ShiftInvariant::Matern {
+                nu: 0.5,
+                length_scale,
+            },
+            |x, y| (-distance(x, y) / length_scale).exp(),
+        );
+        check_approximation(
+            ShiftInvariant::Matern {
+                nu: 1.5,
+                length_scale,
+            },
+            |x, y| {
+                let t = 3f64.sqrt() * distance(x, y) / length_scale;
+                (1.0 + t) * (-t).exp()
+            },
+        );
+    }
+
+    #[test]
+    fn same_seed_gives_same_features() {
+        let kernel = ShiftInvariant::Matern {
+            nu: 2.5,
+            length_scale: 1.0,
+        };
+        let x = [0.3, -0.7, 1.1];
+        let features = RandomFourierFeatures::new(&kernel, 3, 50, 7);
+        assert_eq!(features.num_features(), 50);
+        assert_eq!(
+            features.transform(&x),
+            RandomFourierFeatures::new(&kernel, 3, 50, 7).transform(&x)
+        );
+        assert_ne!(
+            features.transform(&x),
+            RandomFourierFeatures::new(&kernel, 3, 50, 8).transform(&x)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "dimension of samples should be positive")]
+    fn zero_dimension() {
+        RandomFourierFeatures::new(&ShiftInvariant::Gaussian(1.0), 0, 10, 0);
+    }
+}
@@ -1,6 +1,9 @@
 //! Gaussian kernel matrix
-use super::{Kernel, RowKernel};
-use ndarray::ArrayView2;
+use super::{compute_chunked, Kernel, RowKernel};
+use ndarray::{ArrayView1, ArrayView2};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
 
 /// Computes simple Gaussian kernel function.
 pub fn kernel(xi: &[f64], xj: &[f64], gamma: f64) -> f64 {
@@ -11,14 +14,65 @@ pub fn kernel(xi: &[f64], xj: &[f64], gamma: f64) -> f64 {
     (-gamma * dij).exp()
 }
 
-/// Builds a Gaussian kernel matrix.
+/// A Gaussian kernel matrix for dense samples using precomputed squared norms.
+///
+/// The squared distances are computed as `||xi||² + ||xj||² - 2 xi·xj`.
+/// If the active set covers a large part of the samples, all inner products of a row are computed by a single matrix-vector product
+/// (which is handed over to BLAS if the `lapack` feature is enabled).
+pub struct GaussianKernel<'a> {
+    data: ArrayView2<'a, f64>,
+    norms: Vec<f64>,
+    gamma: f64,
+}
+
+impl<'a> GaussianKernel<'a> {
+    /// Creates a [`GaussianKernel`] for the given feature matrix (one sample per row).
+    pub fn new(data: ArrayView2<'a, f64>, gamma: f64) -> Self {
+        let norms = data.outer_iter().map(|xi| xi.dot(&xi)).collect();
+        GaussianKernel { data, norms, gamma }
+    }
+
+    fn entry(&self, i: usize, j: usize, dot: f64) -> f64 {
+        let dij = f64::max(self.norms[i] + self.norms[j] - 2.0 * dot, 0.0);
+        (-self.gamma * dij).exp()
+    }
+}
+
+impl Kernel for GaussianKernel<'_> {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let n = self.size();
+        let i = i % n;
+        let xi: ArrayView1<f64> = self.data.row(i);
+        if 2 * active_set.len() >= n {
+            let dots = self.data.dot(&xi);
+            compute_chunked(ki, active_set, &|ki, active_set| {
+                for (idx_j, &j) in active_set.iter().enumerate() {
+                    let j = j % n;
+                    ki[idx_j] = self.entry(i, j, dots[j]);
+                }
+            });
+        } else {
+            compute_chunked(ki, active_set, &|ki, active_set| {
+                for (idx_j, &j) in active_set.iter().enumerate() {
+                    let j = j % n;
+                    ki[idx_j] = self.entry(i, j, xi.dot(&self.data.row(j)));
+                }
+            });
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.data.nrows()
+    }
+
+    fn diag(&self, _i: usize) -> f64 {
+        1.0
+    }
+}
+
+/// Builds a Gaussian kernel matrix (a [`GaussianKernel`] using precomputed squared norms).
 pub fn from_array<'a>(arr: &'a ArrayView2<'a, f64>, gamma: f64) -> impl Kernel + 'a {
-    let data = arr.outer_iter().collect();
-    RowKernel::new(
-        data,
-        Box::new(move |&xi, &xj| kernel(xi.as_slice().unwrap(), xj.as_slice().unwrap(), gamma)),
-        Box::new(move |&_xi| 1.0),
-    )
+    GaussianKernel::new(arr.view(), gamma)
 }
 
 /// Builds a Gaussian kernel matrix.
@@ -29,3 +83,194 @@ pub fn from_vecs<'a>(data: Vec<&'a [f64]>, gamma: f64) -> impl Kernel + 'a {
         Box::new(move |&_xi| 1.0),
     )
 }
+
+/// Statistics of the median heuristic (see [`median_heuristic`])
+#[derive(Clone, Debug)]
+pub struct MedianHeuristic {
+    /// Chosen kernel parameter `γ = 1 / (2 σ²)` with median distance `σ` (`1` if `σ` vanishes or no pairs are available)
+    pub gamma: f64,
+    /// Median of the pairwise distances
+    pub median_distance: f64,
+    /// Number of (sub)sampled samples
+    pub num_samples: usize,
+    /// Number of pairs used for the median
+    pub num_pairs: usize,
+}
+
+/// Statistics of the Jaakkola heuristic (see [`jaakkola_heuristic`])
+#[derive(Clone, Debug)]
+pub struct JaakkolaHeuristic {
+    /// Chosen kernel parameter `γ = 1 / (2 σ²)` with median distance `σ` to the nearest sample of a different class
+    /// (`1` if `σ` vanishes or no such samples are available)
+    pub gamma: f64,
+    /// Median of the distances to the nearest sample of a different class
+    pub median_distance: f64,
+    /// Number of (sub)sampled samples having a sample of a different class
+    pub num_samples: usize,
+}
+
+/// Statistics of the scale heuristic (see [`scale_heuristic`])
+#[derive(Clone, Debug)]
+pub struct ScaleHeuristic {
+    /// Chosen kernel parameter `γ = 1 / (d · var)`
+    pub gamma: f64,
+    /// Dimension `d` of the samples
+    pub dim: usize,
+    /// Variance `var` of all entries of the feature matrix
+    pub variance: f64,
+}
+
+fn subsample(n: usize, max_samples: usize, seed: u64) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut idxs = sample(&mut rng, n, usize::min(n, max_samples)).into_vec();
+    idxs.sort_unstable();
+    idxs
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    let len = values.len();
+    if len == 0 {
+        return f64::NAN;
+    }
+    let (_, &mut upper, _) = values.select_nth_unstable_by(len / 2, f64::total_cmp);
+    if len % 2 == 1 {
+        return upper;
+    }
+    let lower = values[..len / 2]
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    0.5 * (lower + upper)
+}
+
+/// Computes `γ = 1 / (2 σ²)` for a distance `σ` (falling back to `1` if `σ` vanishes or is undefined).
+fn gamma_from_distance(distance: f64) -> f64 {
+    if distance > 0.0 {
+        0.5 / (distance * distance)
+    } else {
+        1.0
+    }
+}
+
+fn dist_sqr(xi: ArrayView1<f64>, xj: ArrayView1<f64>) -> f64 {
+    xi.iter()
+        .zip(xj.iter())
+        .map(|(xik, xjk)| (xik - xjk) * (xik - xjk))
+        .sum()
+}
+
+/// Chooses `γ` by the median of the pairwise distances of (at most `max_samples`) randomly chosen samples.
+pub fn median_heuristic(data: &ArrayView2<f64>, max_samples: usize, seed: u64) -> MedianHeuristic {
+    let idxs = subsample(data.nrows(), max_samples, seed);
+    let mut dists = Vec::with_capacity(idxs.len() * idxs.len().saturating_sub(1) / 2);
+    for (pos, &i) in idxs.iter().enumerate() {
+        for &j in idxs[pos + 1..].iter() {
+            dists.push(dist_sqr(data.row(i), data.row(j)).sqrt());
+        }
+    }
+    let median_distance = median(&mut dists);
+    MedianHeuristic {
+        gamma: gamma_from_distance(median_distance),
+        median_distance,
+        num_samples: idxs.len(),
+        num_pairs: dists.len(),
+    }
+}
+
+/// Chooses `γ` by the median distance of (at most `max_samples`) randomly chosen samples to the nearest sample with a different label (Jaakkola et al.).
+pub fn jaakkola_heuristic(
+    data: &ArrayView2<f64>,
+    labels: &[f64],
+    max_samples: usize,
+    seed: u64,
+) -> JaakkolaHeuristic {
+    let idxs = subsample(data.nrows(), max_samples, seed);
+    let mut dists: Vec<f64> = idxs
+        .iter()
+        .filter_map(|&i| {
+            idxs.iter()
+                .filter(|&&j| labels[j] != labels[i])
+                .map(|&j| dist_sqr(data.row(i), data.row(j)))
+                .min_by(f64::total_cmp)
+                .map(f64::sqrt)
+        })
+        .collect();
+    let median_distance = median(&mut dists);
+    JaakkolaHeuristic {
+        gamma: gamma_from_distance(median_distance),
+        median_distance,
+        num_samples: dists.len(),
+    }
+}
+
+/// Chooses `γ = 1 / (d · var)` using the dimension `d` and the variance `var` of all entries of the feature matrix.
+pub fn scale_heuristic(data: &ArrayView2<f64>) -> ScaleHeuristic {
+    let dim = data.ncols();
+    let count = data.len() as f64;
+    let mean = data.sum() / count;
+    let variance = data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / count;
+    let gamma = if variance > 0.0 {
+        1.0 / (dim as f64 * variance)
+    } else {
+        1.0
+    };
+    ScaleHeuristic {
+        gamma,
+        dim,
+        variance,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::samples;
+    use ndarray::{array, Array2};
+
+    #[test]
+    fn rows_agree_with_kernel_function() {
+        let x = samples(40);
+        let data = Array2::from_shape_fn((40, 2), |(i, k)| x[i][k]);
+        let kernel = GaussianKernel::new(data.view(), 0.7);
+        // active sets below and above the switch to a single matrix-vector product (with wrapped indices)
+        let small: Vec<usize> = vec![3, 17, 45, 0];
+        let large: Vec<usize> = (0..80).rev().collect();
+        for active_set in [small, large] {
+            let mut ki = vec![0.0; active_set.len()];
+            for i in [0, 11, 52] {
+                kernel.compute_row(i, &mut ki, &active_set);
+                for (kij, &j) in ki.iter().zip(active_set.iter()) {
+                    let expected = super::kernel(&x[i % 40], &x[j % 40], 0.7);
+                    assert!((kij - expected).abs() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn heuristics_with_distinct_samples() {
+        let data = array![[0.0, 0.0], [3.0, 4.0]];
+        let median = median_heuristic(&data.view(), 10, 0);
+        assert_eq!(median.median_distance, 5.0);
+        assert_eq!(median.gamma, 0.5 / 25.0);
+        let jaakkola = jaakkola_heuristic(&data.view(), &[1.0, -1.0], 10, 0);
+        assert_eq!(jaakkola.median_distance, 5.0);
+        assert_eq!(jaakkola.gamma, 0.5 / 25.0);
+    }
+
+    #[test]
+    fn heuristics_with_degenerate_samples() {
+        let duplicates = array![[1.0, 2.0], [1.0, 2.0], [1.0, 2.0]];
+        let empty = Array2::<f64>::zeros((0, 2));
+        for data in [duplicates.view(), empty.view()] {
+            let labels = vec![1.0; data.nrows()];
+            assert_eq!(median_heuristic(&data, 10, 0).gamma, 1.0);
+            assert_eq!(jaakkola_heuristic(&data, &labels, 10, 0).gamma, 1.0);
+        }
+        let labels = [1.0, -1.0, 1.0];
+        assert_eq!(
+            jaakkola_heuristic(&duplicates.view(), &labels, 10, 0).gamma,
+            1.0
+        );
+    }
+}
@@ -0,0 +1,208 @@
+//! Graph kernels for graphs with labeled nodes
+use super::sparse::SparseRow;
+use super::{Kernel, RowKernel};
+
+/// An undirected graph given by adjacency lists and node labels
+#[derive(Clone, Debug)]
+pub struct Graph {
+    adjacency: Vec<Vec<usize>>,
+    labels: Vec<u64>,
+}
+
+impl Graph {
+    /// Creates a [`Graph`] from the adjacency lists and labels of its nodes.
+    pub fn new(adjacency: Vec<Vec<usize>>, labels: Vec<u64>) -> Self {
+        assert_eq!(
+            adjacency.len(),
+            labels.len(),
+            "adjacency lists and labels should have the same length"
+        );
+        assert!(
+            adjacency.iter().flatten().all(|&j| j < labels.len()),
+            "neighbors should be valid nodes"
+        );
+        Graph { adjacency, labels }
+    }
+
+    /// Creates a [`Graph`] from node labels and a list of (undirected) edges.
+    pub fn from_edges(labels: Vec<u64>, edges: &[(usize, usize)]) -> Self {
+        assert!(
+            edges
+                .iter()
+                .all(|&(i, j)| i < labels.len() && j < labels.len()),
+            "edges should connect valid nodes"
+        );
+        let mut adjacency = vec![Vec::new(); labels.len()];
+        for &(i, j) in edges.iter() {
+            adjacency[i].push(j);
+            if i != j {
+                adjacency[j].push(i);
+            }
+        }
+        Graph::new(adjacency, labels)
+    }
+
+    /// Returns the number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Returns the neighbors of the ith node.
+    pub fn neighbors(&self, i: usize) -> &[usize] {
+        &self.adjacency[i]
+    }
+
+    /// Returns the node labels.
+    pub fn labels(&self) -> &[u64] {
+        &self.labels
+    }
+}
+
+/// Mixes a value into a hash (based on the SplitMix64 finalizer).
+fn mix(hash: u64, value: u64) -> u64 {
+    let mut z = (hash ^ value).wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// Reduces a hash to a feature index (folding the upper half into the lower one if indices have less than 64 bits).
+fn feature_index(hash: u64) -> usize {
+    if usize::BITS < u64::BITS {
+        (hash ^ (hash >> 32)) as usize
+    } else {
+        hash as usize
+    }
+}
+
+/// Weisfeiler–Lehman subtree features
+///
+/// In every iteration each node is relabeled by hashing its label together with the sorted labels of its neighbors.
+/// The features of a graph count the occurrences of all (hashed) labels of all iterations.
+/// Since the hash function is fixed, the features of different graphs (e.g. for prediction) are compatible
+/// and the relabeling is computed only once per graph instead of once per pair.
+#[derive(Clone, Debug)]
+pub struct WeisfeilerLehman {
+    iterations: usize,
+    normalized: bool,
+}
+
+impl WeisfeilerLehman {
+    /// Creates the feature map with the given number of relabeling iterations.
+    pub fn new(iterations: usize) -> Self {
+        WeisfeilerLehman {
+            iterations,
+            normalized: false,
+        }
+    }
+
+    /// Scales the features to unit norm (such that `k(g, g) = 1`).
+    pub fn with_normalization(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Computes the node labels of all iterations (starting with the hashed original labels).
+    pub fn relabel(&self, graph: &Graph) -> Vec<Vec<u64>> {
+        let mut labels: Vec<u64> = graph.labels.iter().map(|&l| mix(0, l)).collect();
+        let mut all = Vec::with_capacity(self.iterations + 1);
+        let mut neighbor_labels = Vec::new();
+        for it in 0..self.iterations {
+            let next = (0..graph.num_nodes())
+                .map(|i| {
+                    neighbor_labels.clear();
+                    neighbor_labels.extend(graph.adjacency[i].iter().map(|&j| labels[j]));
+                    neighbor_labels.sort_unstable();
+                    let hash = mix(mix(it as u64 + 1, labels[i]), neighbor_labels.len() as u64);
+                    neighbor_labels.iter().fold(hash, |h, &l| mix(h, l))
+                })
+                .collect();
+            all.push(std::mem::replace(&mut labels, next));
+        }
+        all.push(labels);
+        all
+    }
+
+    /// Computes the (sparse) feature vector of a graph.
+    pub fn features(&self, graph: &Graph) -> SparseRow {
+        let mut hashes: Vec<usize> = self
+            .relabel(graph)
+            .into_iter()
+            .flatten()
+            .map(feature_index)
+            .collect();
+        hashes.sort_unstable();
+        let mut indices: Vec<usize> = Vec::new();
+        let mut values: Vec<f64> = Vec::new();
+        for h in hashes {
+            if indices.last() == Some(&h) {
+                *values.last_mut().unwrap() += 1.0;
+            } else {
+                indices.push(h);
+                values.push(1.0);
+            }
+        }
+        if self.normalized {
+            let norm = values.iter().map(|v| v * v).sum::<f64>().sqrt();
+            for v in values.iter_mut() {
+                *v /= norm;
+            }
+        }
+        SparseRow::new(indices, values)
+    }
+
+    /// Computes the kernel function of two graphs.
+    pub fn compute(&self, gi: &Graph, gj: &Graph) -> f64 {
+        self.features(gi).dot(&self.features(gj))
+    }
+}
+
+/// Builds a Weisfeiler–Lehman subtree kernel matrix (optionally normalized).
+pub fn from_graphs(data: &[Graph], iterations: usize, normalized: bool) -> impl Kernel {
+    let wl = WeisfeilerLehman::new(iterations).with_normalization(normalized);
+    let features = data.iter().map(|g| wl.features(g)).collect();
+    RowKernel::new(
+        features,
+        Box::new(|xi: &SparseRow, xj: &SparseRow| xi.dot(xj)),
+        Box::new(|xi: &SparseRow| xi.norm_sqr()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn features_of_small_graphs() {
+        // path with labels 1 - 2 - 1 and edge with labels 1 - 2
+        let path = Graph::from_edges(vec![1, 2, 1], &[(0, 1), (1, 2)]);
+        let edge = Graph::new(vec![vec![1], vec![0]], vec![1, 2]);
+
+        // labels 1, 2 (original) and (1; 2), (2; 1, 1) after one iteration
+        let wl = WeisfeilerLehman::new(1);
+        let mut counts = wl.features(&path).values().to_vec();
+        counts.sort_by(f64::total_cmp);
+        assert_eq!(counts, [1.0, 1.0, 2.0, 2.0]);
+        assert_eq!(wl.compute(&path, &path), 10.0);
+        assert_eq!(wl.compute(&edge, &edge), 4.0);
+        // shared labels 1, 2 and (1; 2)
+        assert_eq!(wl.compute(&path, &edge), 5.0);
+        assert_eq!(WeisfeilerLehman::new(0).compute(&path, &edge), 3.0);
+
+        let normalized = WeisfeilerLehman::new(1).with_normalization(true);
+        let k = normalized.compute(&path, &edge);
+        assert!((k - 5.0 / 40f64.sqrt()).abs() < 1e-14);
+
+        let kernel = from_graphs(&[path, edge], 1, false);
+        let mut ki = vec![0.0; 2];
+        kernel.compute_row(0, &mut ki, &[0, 1]);
+        assert_eq!(ki, [10.0, 5.0]);
+        assert_eq!(kernel.diag(1), 4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "edges should connect valid nodes")]
+    fn invalid_edge() {
+        Graph::from_edges(vec![1, 2], &[(0, 2)]);
+    }
+}
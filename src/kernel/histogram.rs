@@ -0,0 +1,94 @@
+//! Kernels for (nonnegative) histograms
+use super::{Kernel, RowKernel};
+
+/// Computes the histogram intersection kernel function `Σ min(xₖ, yₖ)`.
+pub fn intersection(xi: &[f64], xj: &[f64]) -> f64 {
+    xi.iter()
+        .zip(xj.iter())
+        .map(|(xik, xjk)| f64::min(*xik, *xjk))
+        .sum()
+}
+
+/// Computes the additive χ² kernel function `Σ 2 xₖ yₖ / (xₖ + yₖ)`.
+pub fn chi2(xi: &[f64], xj: &[f64]) -> f64 {
+    xi.iter()
+        .zip(xj.iter())
+        .filter(|(xik, xjk)| *xik + *xjk > 0.0)
+        .map(|(xik, xjk)| 2.0 * xik * xjk / (xik + xjk))
+        .sum()
+}
+
+/// Computes the χ² distance `Σ (xₖ - yₖ)² / (xₖ + yₖ)`.
+pub fn chi2_dist(xi: &[f64], xj: &[f64]) -> f64 {
+    xi.iter()
+        .zip(xj.iter())
+        .filter(|(xik, xjk)| *xik + *xjk > 0.0)
+        .map(|(xik, xjk)| (xik - xjk) * (xik - xjk) / (xik + xjk))
+        .sum()
+}
+
+/// Computes the exponential χ² kernel function `exp(-γ Σ (xₖ - yₖ)² / (xₖ + yₖ))`.
+pub fn exp_chi2(xi: &[f64], xj: &[f64], gamma: f64) -> f64 {
+    (-gamma * chi2_dist(xi, xj)).exp()
+}
+
+/// Builds a histogram intersection kernel matrix.
+pub fn intersection_from_vecs<'a>(data: Vec<&'a [f64]>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a [f64], xj: &&'a [f64]| intersection(xi, xj)),
+        Box::new(move |xi: &&'a [f64]| xi.iter().sum()),
+    )
+}
+
+/// Builds an additive χ² kernel matrix.
+pub fn chi2_from_vecs<'a>(data: Vec<&'a [f64]>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a [f64], xj: &&'a [f64]| chi2(xi, xj)),
+        Box::new(move |xi: &&'a [f64]| xi.iter().sum()),
+    )
+}
+
+/// Builds an exponential χ² kernel matrix.
+pub fn exp_chi2_from_vecs<'a>(data: Vec<&'a [f64]>, gamma: f64) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a [f64], xj: &&'a [f64]| exp_chi2(xi, xj, gamma)),
+        Box::new(move |&_xi| 1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hand_computed_values() {
+        let x = [1.0, 0.0, 2.0, 3.0];
+        let y = [2.0, 0.0, 1.0, 0.0];
+        assert_eq!(intersection(&x, &y), 2.0);
+        assert!((chi2(&x, &y) - 8.0 / 3.0).abs() < 1e-14);
+        assert!((chi2_dist(&x, &y) - 11.0 / 3.0).abs() < 1e-14);
+        assert!((exp_chi2(&x, &y, 0.5) - (-11.0f64 / 6.0).exp()).abs() < 1e-14);
+        assert_eq!(exp_chi2(&x, &x, 0.5), 1.0);
+
+        let data: Vec<&[f64]> = vec![&x, &y];
+        let kernels: [(Box<dyn Kernel>, [f64; 2]); 3] = [
+            (Box::new(intersection_from_vecs(data.clone())), [6.0, 2.0]),
+            (Box::new(chi2_from_vecs(data.clone())), [6.0, 8.0 / 3.0]),
+            (
+                Box::new(exp_chi2_from_vecs(data, 0.5)),
+                [1.0, (-11.0f64 / 6.0).exp()],
+            ),
+        ];
+        for (kernel, expected) in kernels.iter() {
+            let mut ki = vec![0.0; 2];
+            kernel.compute_row(0, &mut ki, &[0, 1]);
+            for (kij, ej) in ki.iter().zip(expected.iter()) {
+                assert!((kij - ej).abs() < 1e-14);
+            }
+            assert!((kernel.diag(0) - expected[0]).abs() < 1e-14);
+        }
+    }
+}
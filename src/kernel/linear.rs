@@ -0,0 +1,31 @@
+//! Linear kernel matrix
+use super::{Kernel, RowKernel};
+
+/// Computes the linear kernel function (inner product).
+pub fn kernel(xi: &[f64], xj: &[f64]) -> f64 {
+    xi.iter().zip(xj.iter()).map(|(xik, xjk)| xik * xjk).sum()
+}
+
+/// Builds a linear kernel matrix.
+pub fn from_vecs<'a>(data: Vec<&'a [f64]>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a [f64], xj: &&'a [f64]| kernel(xi, xj)),
+        Box::new(move |xi: &&'a [f64]| kernel(xi, xi)),
+    )
+}
+
+/// Computes the weight vector `w = Σ aᵢ xᵢ / λ` of the decision function `w·x + b`.
+pub fn weights(data: &[&[f64]], a: &[f64], lambda: f64) -> Vec<f64> {
+    let dim = data.first().map_or(0, |x| x.len());
+    let mut w = vec![0.0; dim];
+    for (i, &ai) in a.iter().enumerate() {
+        if ai == 0.0 {
+            continue;
+        }
+        for (wk, xik) in w.iter_mut().zip(data[i % data.len()].iter()) {
+            *wk += ai / lambda * xik;
+        }
+    }
+    w
+}
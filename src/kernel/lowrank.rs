@@ -0,0 +1,138 @@
+use super::Kernel;
+use crate::linalg::dot;
+
+/// Factor `G` of a kernel matrix `K = G Gᵀ` (one row of length `rank` per sample)
+pub struct Factor<'a> {
+    values: &'a [f64],
+    rank: usize,
+}
+
+impl<'a> Factor<'a> {
+    /// Creates a [`Factor`] from its entries (row major, `n × rank`).
+    pub fn new(values: &'a [f64], rank: usize) -> Self {
+        Factor { values, rank }
+    }
+
+    /// Returns the rank of the factor.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Returns the number of samples.
+    pub fn size(&self) -> usize {
+        self.values.len().checked_div(self.rank).unwrap_or(0)
+    }
+
+    /// Returns the ith row of the factor.
+    pub fn row(&self, i: usize) -> &'a [f64] {
+        if self.rank == 0 {
+            return &[];
+        }
+        let i = i % self.size();
+        &self.values[i * self.rank..(i + 1) * self.rank]
+    }
+}
+
+/// A low-rank approximation `K ≈ G Gᵀ` of a kernel matrix computed by pivoted incomplete Cholesky factorization
+///
+/// Only one row of the original kernel matrix per column of `G` is computed.
+/// The factor is available via [`Kernel::factor`] such that Newton's method can use it to solve its linear systems
+/// (see [`LinearSolver::Woodbury`](crate::newton::LinearSolver::Woodbury)).
+pub struct LowRankKernel {
+    n: usize,
+    rank: usize,
+    pivots: Vec<usize>,
+    /// Factor `G` (row major, `n × rank`)
+    factor: Vec<f64>,
+}
+
+impl LowRankKernel {
+    /// Factorizes the given kernel matrix until the trace of the remainder `K - G Gᵀ` is below `tol` or the rank reaches `max_rank`.
+    pub fn new(base: &dyn Kernel, tol: f64, max_rank: usize) -> Self {
+        let n = base.size();
+        let full_set: Vec<usize> = (0..n).collect();
+        let mut remainder: Vec<f64> = (0..n).map(|i| base.diag(i)).collect();
+        let mut columns: Vec<Vec<f64>> = Vec::new();
+        let mut pivots = Vec::new();
+        let mut kp = vec![0.0; n];
+        while columns.len() < usize::min(max_rank, n) {
+            if remainder.iter().sum::<f64>() <= tol {
+                break;
+            }
+            let (p, &dp) = remainder
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap();
+            if dp <= 0.0 {
+                break;
+            }
+            base.compute_row(p, &mut kp, &full_set);
+            let gpp = dp.sqrt();
+            let mut column = kp.clone();
+            for previous in columns.iter() {
+                let gp = previous[p];
+                for (gi, pi) in column.iter_mut().zip(previous.iter()) {
+                    *gi -= gp * pi;
+                }
+            }
+            for (i, gi) in column.iter_mut().enumerate() {
+                *gi /= gpp;
+                remainder[i] = f64::max(remainder[i] - *gi * *gi, 0.0);
+            }
+            remainder[p] = 0.0;
+            pivots.push(p);
+            columns.push(column);
+        }
+        let rank = columns.len();
+        let mut factor = vec![0.0; n * rank];
+        for (r, column) in columns.iter().enumerate() {
+            for (i, &gi) in column.iter().enumerate() {
+                factor[i * rank + r] = gi;
+            }
+        }
+        LowRankKernel {
+            n,
+            rank,
+            pivots,
+            factor,
+        }
+    }
+
+    /// Returns the rank of the approximation.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Returns the indices of the samples chosen as pivots.
+    pub fn pivots(&self) -> &[usize] {
+        &self.pivots
+    }
+
+    fn row(&self, i: usize) -> &[f64] {
+        let i = i % self.n;
+        &self.factor[i * self.rank..(i + 1) * self.rank]
+    }
+}
+
+impl Kernel for LowRankKernel {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let gi = self.row(i);
+        for (idx_j, &j) in active_set.iter().enumerate() {
+            ki[idx_j] = dot(gi, self.row(j));
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        let gi = self.row(i);
+        dot(gi, gi)
+    }
+
+    fn factor(&self) -> Option<Factor<'_>> {
+        Some(Factor::new(&self.factor, self.rank))
+    }
+}
@@ -0,0 +1,160 @@
+use super::Kernel;
+use memmap2::{Mmap, MmapMut};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"RUSVMKRN";
+const HEADER_SIZE: usize = 16;
+const ENTRY_SIZE: usize = std::mem::size_of::<f64>();
+
+/// A kernel matrix stored row by row in a memory-mapped file.
+///
+/// This allows to solve problems whose kernel matrix exceeds the main memory (but fits on disk).
+/// The operating system takes care of keeping frequently used rows in memory.
+///
+/// The file consists of a header (8 magic bytes and the number of samples) followed by the full rows as little-endian `f64` values.
+pub struct MappedKernel {
+    n: usize,
+    mmap: Mmap,
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Computes the size of the file for `n` samples (if it does not overflow).
+fn file_size(n: usize) -> Option<usize> {
+    n.checked_mul(n)?
+        .checked_mul(ENTRY_SIZE)?
+        .checked_add(HEADER_SIZE)
+}
+
+impl MappedKernel {
+    /// Computes all rows of the given kernel matrix and writes them to the file at `path` (in parallel if the `parallel` feature is enabled).
+    pub fn build(base: &impl Kernel, path: impl AsRef<Path>) -> io::Result<Self> {
+        let n = base.size();
+        let row_size = n * ENTRY_SIZE;
+        let size = file_size(n).ok_or_else(|| invalid_data("kernel matrix too large"))?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(size as u64)?;
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[..8].copy_from_slice(MAGIC);
+        mmap[8..HEADER_SIZE].copy_from_slice(&(n as u64).to_le_bytes());
+
+        let full_set: Vec<usize> = (0..n).collect();
+        let write_row = |(i, row): (usize, &mut [u8])| {
+            let mut ki = vec![0.0; n];
+            base.compute_row(i, &mut ki, &full_set);
+            for (entry, kij) in row.chunks_exact_mut(ENTRY_SIZE).zip(ki) {
+                entry.copy_from_slice(&kij.to_le_bytes());
+            }
+        };
+        if n > 0 {
+            #[cfg(feature = "parallel")]
+            mmap[HEADER_SIZE..]
+                .par_chunks_mut(row_size)
+                .enumerate()
+                .for_each(write_row);
+            #[cfg(not(feature = "parallel"))]
+            mmap[HEADER_SIZE..]
+                .chunks_mut(row_size)
+                .enumerate()
+                .for_each(write_row);
+        }
+        mmap.flush()?;
+        Ok(MappedKernel {
+            n,
+            mmap: mmap.make_read_only()?,
+        })
+    }
+
+    /// Opens a file previously written by [`MappedKernel::build`].
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < HEADER_SIZE || &mmap[..8] != MAGIC {
+            return Err(invalid_data("not a kernel matrix file"));
+        }
+        let n = u64::from_le_bytes(mmap[8..HEADER_SIZE].try_into().unwrap());
+        let n = usize::try_from(n).map_err(|_| invalid_data("kernel matrix too large"))?;
+        if file_size(n) != Some(mmap.len()) {
+            return Err(invalid_data("unexpected size of kernel matrix file"));
+        }
+        Ok(MappedKernel { n, mmap })
+    }
+
+    fn entry(&self, i: usize, j: usize) -> f64 {
+        let offset = HEADER_SIZE + (i * self.n + j) * ENTRY_SIZE;
+        f64::from_le_bytes(self.mmap[offset..offset + ENTRY_SIZE].try_into().unwrap())
+    }
+}
+
+impl Kernel for MappedKernel {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let n = self.size();
+        for (idx_j, &j) in active_set.iter().enumerate() {
+            ki[idx_j] = self.entry(i % n, j % n);
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        let n = self.size();
+        self.entry(i % n, i % n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::testing::{rows, samples};
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rusvm-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn build_and_open() {
+        let x = samples(7);
+        let base = gaussian::from_vecs(rows(&x), 0.5);
+        let path = temp_path("build");
+        MappedKernel::build(&base, &path).unwrap();
+        let kernel = MappedKernel::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(kernel.size(), 7);
+        let active_set: Vec<usize> = vec![1, 4, 6, 8];
+        let mut expected = vec![0.0; 4];
+        let mut ki = vec![0.0; 4];
+        for i in 0..7 {
+            base.compute_row(i, &mut expected, &active_set);
+            kernel.compute_row(i, &mut ki, &active_set);
+            assert_eq!(ki, expected);
+            assert_eq!(kernel.diag(i), base.diag(i));
+        }
+    }
+
+    #[test]
+    fn open_rejects_overflowing_header() {
+        let path = temp_path("overflow");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(MAGIC).unwrap();
+        file.write_all(&u64::MAX.to_le_bytes()).unwrap();
+        drop(file);
+        let result = MappedKernel::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
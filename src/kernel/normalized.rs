@@ -0,0 +1,144 @@
+use super::{Kernel, KernelFunction};
+use crate::{predict, Status};
+
+fn scale(kii: f64) -> f64 {
+    if kii > 0.0 {
+        1.0 / kii.sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// A kernel matrix with entries `K_ij / sqrt(K_ii K_jj)` (cosine normalization) of a base kernel matrix
+pub struct NormalizedKernel<'a> {
+    base: Box<dyn Kernel + 'a>,
+    scales: Vec<f64>,
+}
+
+impl<'a> NormalizedKernel<'a> {
+    /// Creates a [`NormalizedKernel`] using the diagonal (see [`Kernel::diag`]) of the base kernel matrix.
+    pub fn new(base: Box<dyn Kernel + 'a>) -> Self {
+        let scales = (0..base.size()).map(|i| scale(base.diag(i))).collect();
+        NormalizedKernel { base, scales }
+    }
+
+    /// Evaluates the decision function for a new sample consistently with the normalized kernel matrix.
+    ///
+    /// The kernel function has to be the one of the base kernel matrix.
+    pub fn predict<T>(
+        &self,
+        elem: &T,
+        data: &[T],
+        status: &Status,
+        lmbda: f64,
+        kernel_function: &KernelFunction<T>,
+    ) -> f64 {
+        let scaled: Vec<(&T, f64)> = data.iter().zip(self.scales.iter().copied()).collect();
+        let scale_elem = scale(kernel_function(elem, elem));
+        predict(
+            &(elem, scale_elem),
+            &scaled,
+            status,
+            lmbda,
+            &|(xi, scale_i): &(&T, f64), (x, scale_x): &(&T, f64)| {
+                kernel_function(xi, x) * scale_i * scale_x
+            },
+        )
+    }
+
+    /// Scales the ith row of the base kernel matrix with entries according to `active_set`.
+    fn rescale(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let n = self.scales.len();
+        let scale_i = self.scales[i % n];
+        for (kij, &j) in ki.iter_mut().zip(active_set.iter()) {
+            *kij *= scale_i * self.scales[j % n];
+        }
+    }
+}
+
+impl<'a> Kernel for NormalizedKernel<'a> {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        self.base.compute_row(i, ki, active_set);
+        self.rescale(i, ki, active_set);
+    }
+
+    fn size(&self) -> usize {
+        self.base.size()
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        if self.scales[i % self.scales.len()] > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn restrict_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.restrict_active(old, new);
+    }
+
+    fn set_active(&mut self, old: &Vec<usize>, new: &Vec<usize>) {
+        self.base.set_active(old, new);
+    }
+
+    fn use_rows(&mut self, idxs: &[usize], active_set: &[usize], fun: &mut dyn FnMut(Vec<&[f64]>)) {
+        // use the rows of the base kernel (e.g., from its cache)
+        let mut kidxs: Vec<Vec<f64>> = Vec::with_capacity(idxs.len());
+        self.base
+            .use_rows(idxs, active_set, &mut |kis: Vec<&[f64]>| {
+                kidxs.extend(kis.into_iter().map(|ki| ki.to_vec()));
+            });
+        for (ki, &i) in kidxs.iter_mut().zip(idxs.iter()) {
+            self.rescale(i, ki, active_set);
+        }
+        fun(kidxs.iter().map(|ki| ki.as_slice()).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::CachedKernel;
+    use crate::testing::{polynomial, polynomial_kernel, samples};
+
+    fn normalized(u: &[f64], v: &[f64]) -> f64 {
+        polynomial(u, v) / (polynomial(u, u) * polynomial(v, v)).sqrt()
+    }
+
+    #[test]
+    fn rows_and_predict_agree_with_brute_force() {
+        let x = samples(10);
+        let base = CachedKernel::from(Box::new(polynomial_kernel(&x)), 10);
+        let mut kernel = NormalizedKernel::new(Box::new(base));
+        let active_set: Vec<usize> = (0..10).rev().collect();
+        let idxs = [3, 7, 3];
+        for _pass in 0..2 {
+            kernel.use_rows(&idxs, &active_set, &mut |kis| {
+                for (ki, &i) in kis.iter().zip(idxs.iter()) {
+                    for (kij, &j) in ki.iter().zip(active_set.iter()) {
+                        assert!((kij - normalized(&x[i], &x[j])).abs() < 1e-14);
+                    }
+                }
+            });
+        }
+        let mut ki = vec![0.0; 10];
+        kernel.compute_row(5, &mut ki, &active_set);
+        for (kij, &j) in ki.iter().zip(active_set.iter()) {
+            assert!((kij - normalized(&x[5], &x[j])).abs() < 1e-14);
+            assert!((kernel.diag(j) - 1.0).abs() < 1e-14);
+        }
+
+        let mut status = Status::new(10);
+        status.a = (0..10).map(|i| (i as f64 * 0.9).sin()).collect();
+        status.b = 0.3;
+        let elem = vec![0.2, -0.4];
+        let expected = status.b
+            + (0..10)
+                .map(|i| status.a[i] * normalized(&x[i], &elem) / 0.5)
+                .sum::<f64>();
+        let kernel_function: KernelFunction<Vec<f64>> = Box::new(|u, v| polynomial(u, v));
+        let v = kernel.predict(&elem, &x, &status, 0.5, &kernel_function);
+        assert!((v - expected).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,344 @@
+//! Nyström low-rank approximation of kernel matrices
+use super::{Factor, Kernel};
+use crate::linalg::{cholesky, cholesky_solve, dot, symmetric_eigen};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::{Rng, SeedableRng};
+
+/// Strategies to select the landmarks of the Nyström approximation
+#[derive(Clone, Debug)]
+pub enum Sampling {
+    /// Landmarks are drawn uniformly at random.
+    Uniform,
+    /// Landmarks are selected by k-means++ seeding in feature space (using squared kernel distances).
+    KMeansPlusPlus,
+    /// Landmarks are drawn according to approximate ridge leverage scores (computed from a uniform pilot sample) with given regularization.
+    ///
+    /// Falls back to the uniform pilot sample if the regularized Gram matrix of its features is not positive definite.
+    LeverageScores(f64),
+}
+
+/// Nyström approximation `K ≈ C W⁺ Cᵀ` of a kernel matrix using `m` landmarks
+///
+/// Here, `C` contains the columns of the landmarks and `W` the kernel matrix of the landmarks.
+/// The approximation is available as a [`Kernel`] and as an explicit feature map `φ` with `K ≈ Φ Φᵀ`.
+pub struct Nystrom {
+    n: usize,
+    landmarks: Vec<usize>,
+    rank: usize,
+    /// Projection `U Λ^(-1/2)` from kernel values of the landmarks to features (row major, `m × rank`)
+    projection: Vec<f64>,
+    /// Features of the training samples (row major, `n × rank`)
+    features: Vec<f64>,
+}
+
+/// Relative threshold for eigenvalues of `W` considered as zero
+const RELATIVE_TOLERANCE: f64 = 1e-12;
+
+/// Draws `m` indices from `0..weights.len()` without replacement with probabilities proportional to `weights`.
+fn sample_weighted(rng: &mut StdRng, weights: &[f64], m: usize) -> Vec<usize> {
+    let mut keys: Vec<(f64, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(i, &wi)| {
+            let u: f64 = rng.gen();
+            let key = if wi > 0.0 {
+                u.ln() / wi
+            } else {
+                f64::NEG_INFINITY
+            };
+            (key, i)
+        })
+        .collect();
+    keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keys.into_iter().take(m).map(|(_key, i)| i).collect()
+}
+
+fn select_kmeans_plus_plus(base: &dyn Kernel, m: usize, rng: &mut StdRng) -> Vec<usize> {
+    let n = base.size();
+    let full_set: Vec<usize> = (0..n).collect();
+    let diag: Vec<f64> = (0..n).map(|i| base.diag(i)).collect();
+    let mut dist = vec![f64::INFINITY; n];
+    let mut landmarks = Vec::with_capacity(m);
+    if n == 0 || m == 0 {
+        return landmarks;
+    }
+    let mut kl = vec![0.0; n];
+    let mut next = rng.gen_range(0..n);
+    while landmarks.len() < m {
+        landmarks.push(next);
+        base.compute_row(next, &mut kl, &full_set);
+        for i in 0..n {
+            let dil = f64::max(diag[i] + diag[next] - 2.0 * kl[i], 0.0);
+            dist[i] = f64::min(dist[i], dil);
+        }
+        let total: f64 = dist.iter().sum();
+        if total <= 0.0 {
+            break;
+        }
+        let mut u = rng.gen::<f64>() * total;
+        next = n - 1;
+        for (i, &di) in dist.iter().enumerate() {
+            if u < di {
+                next = i;
+                break;
+            }
+            u -= di;
+        }
+    }
+    landmarks
+}
+
+impl Nystrom {
+    /// Computes the Nyström approximation of `base` with `m` landmarks selected by `sampling` using the random seed `seed`.
+    pub fn new(base: &dyn Kernel, m: usize, sampling: Sampling, seed: u64) -> Self {
+        let n = base.size();
+        let m = usize::min(m, n);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let landmarks = match sampling {
+            Sampling::Uniform => index::sample(&mut rng, n, m).into_vec(),
+            Sampling::KMeansPlusPlus => select_kmeans_plus_plus(base, m, &mut rng),
+            Sampling::LeverageScores(regularization) => {
+                let pilot = Self::from_landmarks(base, index::sample(&mut rng, n, m).into_vec());
+                match pilot.leverage_scores(regularization) {
+                    Some(scores) => sample_weighted(&mut rng, &scores, m),
+                    None => pilot.landmarks,
+                }
+            }
+        };
+        Self::from_landmarks(base, landmarks)
+    }
+
+    /// Computes the Nyström approximation of `base` for the given landmarks.
+    pub fn from_landmarks(base: &dyn Kernel, landmarks: Vec<usize>) -> Self {
+        let n = base.size();
+        let m = landmarks.len();
+        let full_set: Vec<usize> = (0..n).collect();
+        // compute columns of landmarks (C) and their kernel matrix (W)
+        let mut c = vec![0.0; m * n];
+        for (idx_l, &l) in landmarks.iter().enumerate() {
+            base.compute_row(l, &mut c[idx_l * n..(idx_l + 1) * n], &full_set);
+        }
+        let mut w = vec![0.0; m * m];
+        for (idx_k, &k) in landmarks.iter().enumerate() {
+            for idx_l in 0..m {
+                w[idx_k * m + idx_l] = c[idx_l * n + k];
+            }
+        }
+        // compute projection U Λ^(-1/2) using the positive eigenvalues
+        let (eigenvalues, u) = symmetric_eigen(w, m);
+        let eigenvalue_max = eigenvalues.first().copied().unwrap_or(0.0);
+        let rank = eigenvalues
+            .iter()
+            .take_while(|&&ev| ev > RELATIVE_TOLERANCE * eigenvalue_max)
+            .count();
+        let mut projection = vec![0.0; m * rank];
+        for idx_l in 0..m {
+            for r in 0..rank {
+                projection[idx_l * rank + r] = u[idx_l * m + r] / eigenvalues[r].sqrt();
+            }
+        }
+        // compute features of all samples
+        let mut features = vec![0.0; n * rank];
+        let mut ki = vec![0.0; m];
+        for i in 0..n {
+            for idx_l in 0..m {
+                ki[idx_l] = c[idx_l * n + i];
+            }
+            features[i * rank..(i + 1) * rank].copy_from_slice(&Self::project(
+                &projection,
+                rank,
+                &ki,
+            ));
+        }
+        Nystrom {
+            n,
+            landmarks,
+            rank,
+            projection,
+            features,
+        }
+    }
+
+    fn project(projection: &[f64], rank: usize, k_landmarks: &[f64]) -> Vec<f64> {
+        let mut phi = vec![0.0; rank];
+        for (idx_l, &kl) in k_landmarks.iter().enumerate() {
+            for r in 0..rank {
+                phi[r] += kl * projection[idx_l * rank + r];
+            }
+        }
+        phi
+    }
+
+    /// Returns the indices of the landmarks.
+    pub fn landmarks(&self) -> &[usize] {
+        &self.landmarks
+    }
+
+    /// Returns the rank of the approximation (dimension of the feature map).
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Returns the features of the ith training sample.
+    pub fn features(&self, i: usize) -> &[f64] {
+        let i = i % self.n;
+        &self.features[i * self.rank..(i + 1) * self.rank]
+    }
+
+    /// Computes the features of a new sample given its kernel values with the landmarks (in the order of [`Nystrom::landmarks`]).
+    pub fn transform(&self, k_landmarks: &[f64]) -> Vec<f64> {
+        assert_eq!(
+            k_landmarks.len(),
+            self.landmarks.len(),
+            "one kernel value per landmark expected"
+        );
+        Self::project(&self.projection, self.rank, k_landmarks)
+    }
+
+    /// Computes the features of a new sample `elem` using the training samples `data` and the original kernel function.
+    pub fn transform_sample<T>(
+        &self,
+        elem: &T,
+        data: &[T],
+        kernel_function: &dyn Fn(&T, &T) -> f64,
+    ) -> Vec<f64> {
+        let k_landmarks: Vec<f64> = self
+            .landmarks
+            .iter()
+            .map(|&l| kernel_function(&data[l % data.len()], elem))
+            .collect();
+        self.transform(&k_landmarks)
+    }
+
+    /// Computes the weight vector `w = Σ aᵢ φ(xᵢ) / λ` of the decision function `w·φ(x) + b` in feature space.
+    pub fn weights(&self, a: &[f64], lambda: f64) -> Vec<f64> {
+        let mut w = vec![0.0; self.rank];
+        for (i, &ai) in a.iter().enumerate() {
+            if ai == 0.0 {
+                continue;
+            }
+            for (wr, phi_r) in w.iter_mut().zip(self.features(i)) {
+                *wr += ai / lambda * phi_r;
+            }
+        }
+        w
+    }
+
+    /// Computes the ridge leverage scores `φᵢᵀ (ΦᵀΦ + μI)⁻¹ φᵢ` of the approximation with regularization `μ`.
+    ///
+    /// Returns `None` if `ΦᵀΦ + μI` is not (numerically) positive definite.
+    pub fn leverage_scores(&self, regularization: f64) -> Option<Vec<f64>> {
+        let rank = self.rank;
+        let mut gram = vec![0.0; rank * rank];
+        for i in 0..self.n {
+            let phi = self.features(i);
+            for r in 0..rank {
+                for s in 0..rank {
+                    gram[r * rank + s] += phi[r] * phi[s];
+                }
+            }
+        }
+        for r in 0..rank {
+            gram[r * rank + r] += regularization;
+        }
+        let chol = cholesky(&gram, rank)?;
+        let scores = (0..self.n)
+            .map(|i| {
+                let phi = self.features(i);
+                let mut z = phi.to_vec();
+                cholesky_solve(&chol, rank, &mut z);
+                dot(phi, &z)
+            })
+            .collect();
+        Some(scores)
+    }
+}
+
+impl Kernel for Nystrom {
+    fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
+        let phi_i = self.features(i);
+        for (idx_j, &j) in active_set.iter().enumerate() {
+            ki[idx_j] = dot(phi_i, self.features(j));
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.n
+    }
+
+    fn diag(&self, i: usize) -> f64 {
+        let phi_i = self.features(i);
+        dot(phi_i, phi_i)
+    }
+
+    fn factor(&self) -> Option<Factor<'_>> {
+        Some(Factor::new(&self.features, self.rank))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::testing::{rows, samples};
+
+    #[test]
+    fn all_landmarks_reproduce_kernel() {
+        let x = samples(12);
+        let base = gaussian::from_vecs(rows(&x), 0.5);
+        let full_set: Vec<usize> = (0..12).collect();
+        for sampling in [
+            Sampling::Uniform,
+            Sampling::KMeansPlusPlus,
+            Sampling::LeverageScores(1e-3),
+        ] {
+            let approx = Nystrom::new(&base, 12, sampling, 1);
+            let mut expected = vec![0.0; 12];
+            let mut ki = vec![0.0; 12];
+            for i in 0..12 {
+                base.compute_row(i, &mut expected, &full_set);
+                approx.compute_row(i, &mut ki, &full_set);
+                for (kij, ej) in ki.iter().zip(expected.iter()) {
+                    assert!((kij - ej).abs() < 1e-8);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn kmeans_plus_plus_selects_distinct_landmarks() {
+        let x = samples(12);
+        let base = gaussian::from_vecs(rows(&x), 0.5);
+        let approx = Nystrom::new(&base, 5, Sampling::KMeansPlusPlus, 3);
+        let mut landmarks = approx.landmarks().to_vec();
+        landmarks.sort();
+        landmarks.dedup();
+        assert_eq!(landmarks.len(), 5);
+        assert_eq!(approx.rank(), 5);
+    }
+
+    #[test]
+    fn leverage_scores_sum_to_rank() {
+        let x = samples(12);
+        let base = gaussian::from_vecs(rows(&x), 0.5);
+        let approx = Nystrom::new(&base, 6, Sampling::Uniform, 2);
+        let scores = approx.leverage_scores(0.0).unwrap();
+        let sum: f64 = scores.iter().sum();
+        assert!((sum - approx.rank() as f64).abs() < 1e-8);
+        assert!(scores
+            .iter()
+            .all(|&si| (-1e-12..=1.0 + 1e-12).contains(&si)));
+        assert!(approx.leverage_scores(-1e3).is_none());
+        let fallback = Nystrom::new(&base, 6, Sampling::LeverageScores(-1e3), 2);
+        assert_eq!(fallback.landmarks().len(), 6);
+    }
+
+    #[test]
+    fn empty_kernel() {
+        let base = gaussian::from_vecs(Vec::new(), 0.5);
+        let approx = Nystrom::new(&base, 5, Sampling::KMeansPlusPlus, 0);
+        assert!(approx.landmarks().is_empty());
+        assert_eq!(approx.rank(), 0);
+    }
+}
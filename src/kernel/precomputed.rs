@@ -1,34 +1,217 @@
 use super::Kernel;
-use ndarray::Array2;
+use ndarray::ArrayView2;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Floating point type used to store the entries of a [`PrecomputedKernel`]
+pub trait PrecomputedEntry: Copy + Send + Sync {
+    /// Converts a value to the storage type.
+    fn from_f64(value: f64) -> Self;
+    /// Converts a stored value back.
+    fn to_f64(self) -> f64;
+}
+
+impl PrecomputedEntry for f64 {
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl PrecomputedEntry for f32 {
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
 
 /// A struct to precompute rows of a kernel matrix.
-pub struct PrecomputedKernel {
+///
+/// Only the lower triangle of the (symmetric) matrix is stored in packed form.
+/// The entries are stored as `f64` by default, `f32` halves the memory consumption.
+pub struct PrecomputedKernel<F = f64> {
     n: usize,
-    kernel_matrix: Array2<f64>,
+    kernel_matrix: Vec<F>,
+}
+
+fn packed_index(i: usize, j: usize) -> usize {
+    if i >= j {
+        i * (i + 1) / 2 + j
+    } else {
+        j * (j + 1) / 2 + i
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
 }
 
-impl PrecomputedKernel {
+impl PrecomputedKernel<f64> {
     /// Generates a precomputed version of the given kernel matrix.
     pub fn from(base: &impl Kernel) -> Self {
+        Self::from_kernel(base)
+    }
+}
+
+impl<F: PrecomputedEntry> PrecomputedKernel<F> {
+    /// Generates a precomputed version of the given kernel matrix.
+    pub fn from_kernel(base: &impl Kernel) -> Self {
         let n = base.size();
-        let mut kernel_matrix = ndarray::Array::zeros((n, n));
+        let mut kernel_matrix = Vec::with_capacity(n * (n + 1) / 2);
+        let mut ki = vec![0.0; n];
         for i in 0..n {
-            let mut row_i = kernel_matrix.row_mut(i);
-            let ki = row_i.as_slice_mut().unwrap();
-            base.compute_row(i, ki, Vec::from_iter(0..=i).as_slice());
-            for j in 0..i {
-                kernel_matrix[(j, i)] = kernel_matrix[(i, j)];
-            }
+            base.compute_row(i, &mut ki, Vec::from_iter(0..=i).as_slice());
+            kernel_matrix.extend(ki[..=i].iter().map(|&kij| F::from_f64(kij)));
         }
         PrecomputedKernel { n, kernel_matrix }
     }
+
+    /// Uses the lower triangle of a given (symmetric) kernel matrix.
+    pub fn from_array(mat: &ArrayView2<f64>) -> Self {
+        let n = mat.nrows();
+        assert_eq!(n, mat.ncols(), "kernel matrix should be square");
+        let mut kernel_matrix = Vec::with_capacity(n * (n + 1) / 2);
+        for i in 0..n {
+            kernel_matrix.extend((0..=i).map(|j| F::from_f64(mat[(i, j)])));
+        }
+        PrecomputedKernel { n, kernel_matrix }
+    }
+
+    /// Reads a kernel matrix given as whitespace-separated values (one row per line).
+    ///
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if the matrix is not square or not symmetric.
+    pub fn from_text(reader: impl BufRead) -> io::Result<Self> {
+        let mut rows = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let row = line
+                .split_whitespace()
+                .map(|v| {
+                    v.parse::<f64>()
+                        .map_err(|e| invalid_data(format!("row {}: {}", rows.len() + 1, e)))
+                })
+                .collect::<io::Result<Vec<f64>>>()?;
+            rows.push(row);
+        }
+        let n = rows.len();
+        let mut kernel_matrix = Vec::with_capacity(n * (n + 1) / 2);
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != n {
+                return Err(invalid_data(format!(
+                    "row {} has {} instead of {} entries",
+                    i + 1,
+                    row.len(),
+                    n
+                )));
+            }
+            for j in 0..=i {
+                if row[j] != rows[j][i] {
+                    return Err(invalid_data(format!(
+                        "entries ({}, {}) and ({}, {}) differ",
+                        i + 1,
+                        j + 1,
+                        j + 1,
+                        i + 1
+                    )));
+                }
+                kernel_matrix.push(F::from_f64(row[j]));
+            }
+        }
+        Ok(PrecomputedKernel { n, kernel_matrix })
+    }
+
+    /// Reads a kernel matrix from a file (see [`PrecomputedKernel::from_text`]).
+    pub fn from_text_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_text(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a kernel matrix in the precomputed format of LIBSVM (option `-t 4`) and returns it together with the labels.
+    ///
+    /// Each line has the form `<label> 0:<serial> 1:<K(x, x_1)> ... <n>:<K(x, x_n)>`,
+    /// where the (1-based) serial number identifies the column of the sample itself.
+    /// Returns an error of kind [`io::ErrorKind::InvalidData`] if a serial number is missing or used twice.
+    pub fn from_libsvm(reader: impl BufRead) -> io::Result<(Self, Vec<f64>)> {
+        let mut labels = Vec::new();
+        let mut serials = Vec::new();
+        let mut rows = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            let mut tokens = line.split_whitespace();
+            let label = match tokens.next() {
+                Some(token) => token,
+                None => continue,
+            };
+            let r = labels.len() + 1;
+            labels.push(
+                label
+                    .parse::<f64>()
+                    .map_err(|e| invalid_data(format!("line {}: {}", r, e)))?,
+            );
+            let mut serial = None;
+            let mut row = Vec::new();
+            for token in tokens {
+                let (idx, value) = token
+                    .split_once(':')
+                    .ok_or_else(|| invalid_data(format!("line {}: invalid entry {}", r, token)))?;
+                let idx = idx
+                    .parse::<usize>()
+                    .map_err(|e| invalid_data(format!("line {}: {}", r, e)))?;
+                let value = value
+                    .parse::<f64>()
+                    .map_err(|e| invalid_data(format!("line {}: {}", r, e)))?;
+                if idx == 0 {
+                    serial = Some(value as usize);
+                } else {
+                    if row.len() < idx {
+                        row.resize(idx, 0.0);
+                    }
+                    row[idx - 1] = value;
+                }
+            }
+            match serial {
+                Some(serial) if serial > 0 && serials.contains(&(serial - 1)) => {
+                    return Err(invalid_data(format!(
+                        "line {}: duplicate serial number {}",
+                        r, serial
+                    )))
+                }
+                Some(serial) if serial > 0 => serials.push(serial - 1),
+                _ => return Err(invalid_data(format!("line {}: missing serial number", r))),
+            }
+            rows.push(row);
+        }
+        let n = rows.len();
+        let mut kernel_matrix = Vec::with_capacity(n * (n + 1) / 2);
+        for (i, row) in rows.iter().enumerate() {
+            for &serial_j in serials[..=i].iter() {
+                let kij = *row.get(serial_j).ok_or_else(|| {
+                    invalid_data(format!("line {}: missing column {}", i + 1, serial_j + 1))
+                })?;
+                kernel_matrix.push(F::from_f64(kij));
+            }
+        }
+        Ok((PrecomputedKernel { n, kernel_matrix }, labels))
+    }
+
+    /// Reads a kernel matrix from a file in the precomputed format of LIBSVM (see [`PrecomputedKernel::from_libsvm`]).
+    pub fn from_libsvm_file(path: impl AsRef<Path>) -> io::Result<(Self, Vec<f64>)> {
+        Self::from_libsvm(BufReader::new(File::open(path)?))
+    }
 }
 
-impl super::Kernel for PrecomputedKernel {
+impl<F: PrecomputedEntry> super::Kernel for PrecomputedKernel<F> {
     fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
         let n = self.size();
         for (idx_j, &j) in active_set.iter().enumerate() {
-            ki[idx_j] = self.kernel_matrix[(i % n, j % n)]
+            ki[idx_j] = self.kernel_matrix[packed_index(i % n, j % n)].to_f64();
         }
     }
 
@@ -38,6 +221,77 @@ impl super::Kernel for PrecomputedKernel {
 
     fn diag(&self, i: usize) -> f64 {
         let n = self.size();
-        self.kernel_matrix[(i % n, i % n)]
+        self.kernel_matrix[packed_index(i % n, i % n)].to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::testing::{rows, samples};
+
+    fn check_rows(kernel: &impl Kernel, base: &impl Kernel, tol: f64) {
+        let n = base.size();
+        assert_eq!(kernel.size(), n);
+        // the variables of problems with several variables per sample wrap around
+        let active_set: Vec<usize> = (0..2 * n).rev().collect();
+        let mut ki = vec![0.0; 2 * n];
+        let mut base_ki = vec![0.0; 2 * n];
+        for i in 0..2 * n {
+            kernel.compute_row(i, &mut ki, &active_set);
+            base.compute_row(i, &mut base_ki, &active_set);
+            for (kij, base_kij) in ki.iter().zip(base_ki.iter()) {
+                assert!((kij - base_kij).abs() <= tol);
+            }
+            assert!((kernel.diag(i) - base.diag(i)).abs() <= tol);
+        }
+    }
+
+    #[test]
+    fn from_kernel_reproduces_rows() {
+        let x = samples(7);
+        let base = gaussian::from_vecs(rows(&x), 1.0);
+        check_rows(&PrecomputedKernel::from(&base), &base, 0.0);
+        check_rows(&PrecomputedKernel::<f32>::from_kernel(&base), &base, 1e-6);
+    }
+
+    #[test]
+    fn from_text_reads_lower_triangle() {
+        let kernel =
+            PrecomputedKernel::<f64>::from_text("1 2 3\n\n2 4 5\n3 5 6\n".as_bytes()).unwrap();
+        let mut ki = vec![0.0; 3];
+        kernel.compute_row(1, &mut ki, &[0, 1, 2]);
+        assert_eq!(ki, vec![2.0, 4.0, 5.0]);
+        assert_eq!(kernel.diag(2), 6.0);
+
+        assert!(PrecomputedKernel::<f64>::from_text("1\n2\n".as_bytes()).is_err());
+        assert!(PrecomputedKernel::<f64>::from_text("1\n2 x\n".as_bytes()).is_err());
+        // non-square and non-symmetric matrices
+        for text in ["1 2\n2 4 5\n", "1 2 3\n2 4\n", "1 2\n3 4\n"] {
+            let result = PrecomputedKernel::<f64>::from_text(text.as_bytes());
+            assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::InvalidData));
+        }
+    }
+
+    #[test]
+    fn from_libsvm_uses_serial_numbers() {
+        // samples given in the order 2, 1 with the kernel matrix [[1, 2], [2, 3]]
+        let text = "-1 0:2 1:2 2:3\n+1 0:1 1:1 2:2\n";
+        let (kernel, labels) = PrecomputedKernel::<f64>::from_libsvm(text.as_bytes()).unwrap();
+        assert_eq!(labels, vec![-1.0, 1.0]);
+        let mut ki = vec![0.0; 2];
+        kernel.compute_row(0, &mut ki, &[0, 1]);
+        assert_eq!(ki, vec![3.0, 2.0]);
+        assert_eq!(kernel.diag(1), 1.0);
+
+        assert!(PrecomputedKernel::<f64>::from_libsvm("1 1:1\n".as_bytes()).is_err());
+        assert!(
+            PrecomputedKernel::<f64>::from_libsvm("1 0:1 2:1\n2 0:2 1:1\n".as_bytes()).is_err()
+        );
+        // duplicate serial numbers
+        let text = "1 0:1 1:1 2:2\n2 0:1 1:2 2:3\n";
+        let result = PrecomputedKernel::<f64>::from_libsvm(text.as_bytes());
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::InvalidData));
     }
 }
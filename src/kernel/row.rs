@@ -1,6 +1,17 @@
-/// Function type for kernel functions
+use super::compute_chunked;
+use crate::problem::base::MaybeSync;
+
+/// Function type for kernel functions (which have to be [`Send`] and [`Sync`] if the `parallel` feature is enabled)
+#[cfg(feature = "parallel")]
+pub type KernelFunction<T> = Box<dyn Fn(&T, &T) -> f64 + Send + Sync>;
+/// Function type for kernel functions (which have to be [`Send`] and [`Sync`] if the `parallel` feature is enabled)
+#[cfg(not(feature = "parallel"))]
 pub type KernelFunction<T> = Box<dyn Fn(&T, &T) -> f64>;
 /// Function type for diagonal entries of kernel functions
+#[cfg(feature = "parallel")]
+pub type DiagFunction<T> = Box<dyn Fn(&T) -> f64 + Send + Sync>;
+/// Function type for diagonal entries of kernel functions
+#[cfg(not(feature = "parallel"))]
 pub type DiagFunction<T> = Box<dyn Fn(&T) -> f64>;
 
 /// A struct containing data for the computation of a kernel matrix with kernel function.
@@ -25,13 +36,15 @@ impl<T> RowKernel<T> {
     }
 }
 
-impl<T> super::Kernel for RowKernel<T> {
+impl<T: MaybeSync> super::Kernel for RowKernel<T> {
     fn compute_row(&self, i: usize, ki: &mut [f64], active_set: &[usize]) {
         let xi = &self.data[i % self.data.len()];
-        for (idx_j, &j) in active_set.iter().enumerate() {
-            let xj = &self.data[j % self.data.len()];
-            (*ki)[idx_j] = (self.kernel_function)(&xi, &xj);
-        }
+        compute_chunked(ki, active_set, &|ki, active_set| {
+            for (idx_j, &j) in active_set.iter().enumerate() {
+                let xj = &self.data[j % self.data.len()];
+                (*ki)[idx_j] = (self.kernel_function)(&xi, &xj);
+            }
+        });
     }
 
     fn size(&self) -> usize {
@@ -0,0 +1,229 @@
+//! Kernel matrices for sparse samples
+use super::{Kernel, RowKernel};
+
+/// A sparse sample given by sorted feature indices and corresponding values.
+#[derive(Clone, Debug)]
+pub struct SparseRow {
+    indices: Vec<usize>,
+    values: Vec<f64>,
+    norm_sqr: f64,
+}
+
+impl SparseRow {
+    /// Creates a [`SparseRow`] from (strictly increasing) feature indices and values.
+    pub fn new(indices: Vec<usize>, values: Vec<f64>) -> Self {
+        assert_eq!(
+            indices.len(),
+            values.len(),
+            "indices and values should have the same length"
+        );
+        assert!(
+            indices.windows(2).all(|w| w[0] < w[1]),
+            "indices should be strictly increasing"
+        );
+        let norm_sqr = values.iter().map(|v| v * v).sum();
+        SparseRow {
+            indices,
+            values,
+            norm_sqr,
+        }
+    }
+
+    /// Creates a [`SparseRow`] containing the nonzero entries of a dense sample.
+    pub fn from_dense(x: &[f64]) -> Self {
+        let (indices, values) = x
+            .iter()
+            .enumerate()
+            .filter(|(_k, &xk)| xk != 0.0)
+            .map(|(k, &xk)| (k, xk))
+            .unzip();
+        SparseRow::new(indices, values)
+    }
+
+    /// Splits a matrix in compressed sparse row (CSR) format into its rows.
+    pub fn from_csr(indptr: &[usize], indices: &[usize], values: &[f64]) -> Vec<Self> {
+        indptr
+            .windows(2)
+            .map(|w| SparseRow::new(indices[w[0]..w[1]].to_vec(), values[w[0]..w[1]].to_vec()))
+            .collect()
+    }
+
+    /// Returns the feature indices of the nonzero entries.
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the values of the nonzero entries.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the (precomputed) squared Euclidean norm.
+    pub fn norm_sqr(&self) -> f64 {
+        self.norm_sqr
+    }
+
+    /// Computes the inner product with another sparse sample.
+    pub fn dot(&self, other: &SparseRow) -> f64 {
+        let mut res = 0.0;
+        let (mut p, mut q) = (0, 0);
+        while p < self.indices.len() && q < other.indices.len() {
+            let (k, l) = (self.indices[p], other.indices[q]);
+            if k == l {
+                res += self.values[p] * other.values[q];
+                p += 1;
+                q += 1;
+            } else if k < l {
+                p += 1;
+            } else {
+                q += 1;
+            }
+        }
+        res
+    }
+
+    /// Computes the squared Euclidean distance to another sparse sample.
+    pub fn dist_sqr(&self, other: &SparseRow) -> f64 {
+        f64::max(self.norm_sqr + other.norm_sqr - 2.0 * self.dot(other), 0.0)
+    }
+}
+
+/// Computes the linear kernel function.
+pub fn linear(xi: &SparseRow, xj: &SparseRow) -> f64 {
+    xi.dot(xj)
+}
+
+/// Computes the Gaussian kernel function.
+pub fn gaussian(xi: &SparseRow, xj: &SparseRow, gamma: f64) -> f64 {
+    (-gamma * xi.dist_sqr(xj)).exp()
+}
+
+/// Computes the polynomial kernel function `(gamma * xi·xj + coef0)^degree`.
+pub fn polynomial(xi: &SparseRow, xj: &SparseRow, gamma: f64, coef0: f64, degree: i32) -> f64 {
+    (gamma * xi.dot(xj) + coef0).powi(degree)
+}
+
+/// Builds a linear kernel matrix.
+pub fn linear_from_rows<'a>(data: Vec<&'a SparseRow>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |&xi: &&'a SparseRow, &xj: &&'a SparseRow| linear(xi, xj)),
+        Box::new(move |&xi: &&'a SparseRow| xi.norm_sqr()),
+    )
+}
+
+/// Builds a Gaussian kernel matrix.
+pub fn gaussian_from_rows<'a>(data: Vec<&'a SparseRow>, gamma: f64) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |&xi: &&'a SparseRow, &xj: &&'a SparseRow| gaussian(xi, xj, gamma)),
+        Box::new(move |&_xi| 1.0),
+    )
+}
+
+/// Builds a polynomial kernel matrix.
+pub fn polynomial_from_rows<'a>(
+    data: Vec<&'a SparseRow>,
+    gamma: f64,
+    coef0: f64,
+    degree: i32,
+) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |&xi: &&'a SparseRow, &xj: &&'a SparseRow| {
+            polynomial(xi, xj, gamma, coef0, degree)
+        }),
+        Box::new(move |&xi: &&'a SparseRow| (gamma * xi.norm_sqr() + coef0).powi(degree)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::{gaussian, linear};
+
+    #[test]
+    fn products_with_disjoint_indices() {
+        let x = SparseRow::new(vec![0, 3, 7], vec![1.0, 2.0, -1.0]);
+        let y = SparseRow::new(vec![1, 4, 8], vec![3.0, -2.0, 0.5]);
+        let z = SparseRow::new(vec![3, 8], vec![2.0, 4.0]);
+        assert_eq!(x.dot(&y), 0.0);
+        assert_eq!(x.dist_sqr(&y), 6.0 + 13.25);
+        assert_eq!(x.dot(&z), 4.0);
+        assert_eq!(y.dot(&z), 2.0);
+        assert_eq!(x.dist_sqr(&z), 2.0 + 16.0);
+        let empty = SparseRow::new(Vec::new(), Vec::new());
+        assert_eq!(x.dot(&empty), 0.0);
+        assert_eq!(x.dist_sqr(&empty), x.norm_sqr());
+    }
+
+    #[test]
+    fn from_csr_and_from_dense() {
+        let dense = [
+            vec![0.0, 1.5, 0.0, -2.0],
+            vec![0.0; 4],
+            vec![3.0, 0.0, 0.0, 1.0],
+        ];
+        let rows = SparseRow::from_csr(&[0, 2, 2, 4], &[1, 3, 0, 3], &[1.5, -2.0, 3.0, 1.0]);
+        assert_eq!(rows.len(), 3);
+        for (row, xi) in rows.iter().zip(dense.iter()) {
+            let from_dense = SparseRow::from_dense(xi);
+            assert_eq!(row.indices(), from_dense.indices());
+            assert_eq!(row.values(), from_dense.values());
+            assert_eq!(row.norm_sqr(), linear::kernel(xi, xi));
+        }
+        assert_eq!(rows[0].indices(), [1, 3]);
+        assert!(rows[1].indices().is_empty());
+    }
+
+    #[test]
+    fn sparse_and_dense_kernels_agree() {
+        let dense: Vec<Vec<f64>> = (0..6)
+            .map(|i| {
+                (0..5)
+                    .map(|k| {
+                        if (i + k) % 3 == 0 {
+                            0.0
+                        } else {
+                            ((i * 5 + k) as f64).sin()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let sparse: Vec<SparseRow> = dense.iter().map(|xi| SparseRow::from_dense(xi)).collect();
+        let dense_rows: Vec<&[f64]> = dense.iter().map(|xi| xi.as_slice()).collect();
+        let kernels: [(Box<dyn Kernel>, Box<dyn Kernel>); 2] = [
+            (
+                Box::new(linear_from_rows(sparse.iter().collect())),
+                Box::new(linear::from_vecs(dense_rows.clone())),
+            ),
+            (
+                Box::new(gaussian_from_rows(sparse.iter().collect(), 0.3)),
+                Box::new(gaussian::from_vecs(dense_rows.clone(), 0.3)),
+            ),
+        ];
+        let active_set: Vec<usize> = (0..6).rev().collect();
+        let (mut ki_sparse, mut ki_dense) = (vec![0.0; 6], vec![0.0; 6]);
+        for (sparse_kernel, dense_kernel) in kernels.iter() {
+            for i in 0..6 {
+                sparse_kernel.compute_row(i, &mut ki_sparse, &active_set);
+                dense_kernel.compute_row(i, &mut ki_dense, &active_set);
+                for (kij_sparse, kij_dense) in ki_sparse.iter().zip(ki_dense.iter()) {
+                    assert!((kij_sparse - kij_dense).abs() < 1e-12);
+                }
+                assert!((sparse_kernel.diag(i) - dense_kernel.diag(i)).abs() < 1e-12);
+            }
+        }
+
+        let kernel = polynomial_from_rows(sparse.iter().collect(), 0.5, 1.0, 3);
+        for i in 0..6 {
+            kernel.compute_row(i, &mut ki_sparse, &active_set);
+            for (kij, &j) in ki_sparse.iter().zip(active_set.iter()) {
+                let expected = (0.5 * linear::kernel(&dense[i], &dense[j]) + 1.0).powi(3);
+                assert!((kij - expected).abs() < 1e-12);
+            }
+            assert!((kernel.diag(i) - ki_sparse[5 - i]).abs() < 1e-12);
+        }
+    }
+}
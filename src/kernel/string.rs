@@ -0,0 +1,291 @@
+//! String kernels for sequences of bytes (e.g. `&str` or `&[u8]`)
+use super::{Kernel, RowKernel};
+use crate::problem::base::{MaybeSend, MaybeSync};
+
+/// Sorted k-mer counts of a sequence (feature vector of the spectrum kernel)
+#[derive(Clone, Debug)]
+pub struct Spectrum {
+    counts: Vec<(Vec<u8>, f64)>,
+}
+
+impl Spectrum {
+    /// Counts the contiguous substrings of length `k` of `s`.
+    pub fn new(s: &[u8], k: usize) -> Self {
+        let mut kmers: Vec<&[u8]> = if k > 0 {
+            s.windows(k).collect()
+        } else {
+            Vec::new()
+        };
+        kmers.sort_unstable();
+        let mut counts: Vec<(Vec<u8>, f64)> = Vec::new();
+        for kmer in kmers {
+            match counts.last_mut() {
+                Some((last, count)) if last.as_slice() == kmer => *count += 1.0,
+                _ => counts.push((kmer.to_vec(), 1.0)),
+            }
+        }
+        Spectrum { counts }
+    }
+
+    /// Computes the inner product of two spectra.
+    pub fn dot(&self, other: &Spectrum) -> f64 {
+        let mut res = 0.0;
+        let (mut p, mut q) = (0, 0);
+        while p < self.counts.len() && q < other.counts.len() {
+            let (u, cu) = &self.counts[p];
+            let (v, cv) = &other.counts[q];
+            match u.cmp(v) {
+                std::cmp::Ordering::Equal => {
+                    res += cu * cv;
+                    p += 1;
+                    q += 1;
+                }
+                std::cmp::Ordering::Less => p += 1,
+                std::cmp::Ordering::Greater => q += 1,
+            }
+        }
+        res
+    }
+}
+
+/// Computes the spectrum kernel (number of pairs of common substrings of length `k`).
+pub fn spectrum(s: &[u8], t: &[u8], k: usize) -> f64 {
+    Spectrum::new(s, k).dot(&Spectrum::new(t, k))
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// Mismatch kernel with k-mers of length `k`, at most `m` mismatches and an alphabet of size `alphabet_size`
+pub struct Mismatch {
+    k: usize,
+    /// Number of k-mers within distance `m` of two k-mers with given Hamming distance
+    common: Vec<f64>,
+}
+
+impl Mismatch {
+    /// Creates the [`Mismatch`] kernel for the given parameters.
+    pub fn new(k: usize, m: usize, alphabet_size: usize) -> Self {
+        let l = alphabet_size as f64;
+        let common = (0..=k)
+            .map(|d| {
+                // i: changed positions among the k - d agreeing positions,
+                // j/r: differing positions taken from the first/second k-mer
+                let mut count = 0.0;
+                for i in 0..=(k - d) {
+                    for j in 0..=d {
+                        for r in 0..=(d - j) {
+                            if i + d - j <= m && i + d - r <= m {
+                                count += binomial(k - d, i)
+                                    * (l - 1.0).powi(i as i32)
+                                    * binomial(d, j)
+                                    * binomial(d - j, r)
+                                    * (l - 2.0).powi((d - j - r) as i32);
+                            }
+                        }
+                    }
+                }
+                count
+            })
+            .collect();
+        Mismatch { k, common }
+    }
+
+    /// Computes the kernel function.
+    pub fn compute(&self, s: &[u8], t: &[u8]) -> f64 {
+        if self.k == 0 {
+            return 0.0;
+        }
+        let mut res = 0.0;
+        for a in s.windows(self.k) {
+            for b in t.windows(self.k) {
+                let d = a.iter().zip(b).filter(|(ak, bk)| ak != bk).count();
+                res += self.common[d];
+            }
+        }
+        res
+    }
+}
+
+/// Computes the mismatch kernel (see [`Mismatch`]).
+pub fn mismatch(s: &[u8], t: &[u8], k: usize, m: usize, alphabet_size: usize) -> f64 {
+    Mismatch::new(k, m, alphabet_size).compute(s, t)
+}
+
+/// Computes the (gap-weighted) subsequence kernel for subsequences of length `n` with decay factor `decay`.
+pub fn subsequence(s: &[u8], t: &[u8], n: usize, decay: f64) -> f64 {
+    if n == 0 {
+        return 1.0;
+    }
+    let (ls, lt) = (s.len(), t.len());
+    let width = lt + 1;
+    let decay2 = decay * decay;
+    // kp[a * width + b] contains K'_i(s[..a], t[..b])
+    let mut kp = vec![1.0; (ls + 1) * width];
+    for i in 1..n {
+        let mut kp_next = vec![0.0; (ls + 1) * width];
+        for a in i..=ls {
+            let mut kpp = 0.0;
+            for b in i..=lt {
+                kpp *= decay;
+                if s[a - 1] == t[b - 1] {
+                    kpp += decay2 * kp[(a - 1) * width + b - 1];
+                }
+                kp_next[a * width + b] = decay * kp_next[(a - 1) * width + b] + kpp;
+            }
+        }
+        kp = kp_next;
+    }
+    let mut res = 0.0;
+    for a in 1..=ls {
+        for b in 1..=lt {
+            if s[a - 1] == t[b - 1] {
+                res += decay2 * kp[(a - 1) * width + b - 1];
+            }
+        }
+    }
+    res
+}
+
+/// Normalizes a kernel value by the values of both samples with themselves.
+pub fn normalize(kst: f64, kss: f64, ktt: f64) -> f64 {
+    if kss > 0.0 && ktt > 0.0 {
+        kst / (kss * ktt).sqrt()
+    } else {
+        0.0
+    }
+}
+
+fn from_samples<T: MaybeSync>(
+    data: Vec<(T, f64)>,
+    fun: impl Fn(&T, &T) -> f64 + MaybeSend + MaybeSync + 'static,
+    normalized: bool,
+) -> RowKernel<(T, f64)> {
+    RowKernel::new(
+        data,
+        Box::new(move |(s, kss), (t, ktt)| {
+            let kst = fun(s, t);
+            if normalized {
+                normalize(kst, *kss, *ktt)
+            } else {
+                kst
+            }
+        }),
+        Box::new(move |(_s, kss)| {
+            if normalized {
+                normalize(*kss, *kss, *kss)
+            } else {
+                *kss
+            }
+        }),
+    )
+}
+
+/// Builds a spectrum kernel matrix (optionally normalized).
+pub fn spectrum_from_strs<S: AsRef<[u8]>>(data: Vec<S>, k: usize, normalized: bool) -> impl Kernel {
+    let data = data
+        .into_iter()
+        .map(|s| {
+            let phi = Spectrum::new(s.as_ref(), k);
+            let kss = phi.dot(&phi);
+            (phi, kss)
+        })
+        .collect();
+    from_samples(
+        data,
+        |phi: &Spectrum, psi: &Spectrum| phi.dot(psi),
+        normalized,
+    )
+}
+
+/// Builds a mismatch kernel matrix (optionally normalized).
+pub fn mismatch_from_strs<'a, S: AsRef<[u8]> + MaybeSync + 'a>(
+    data: Vec<S>,
+    k: usize,
+    m: usize,
+    alphabet_size: usize,
+    normalized: bool,
+) -> impl Kernel + 'a {
+    let mismatch = Mismatch::new(k, m, alphabet_size);
+    let data = data
+        .into_iter()
+        .map(|s| {
+            let kss = mismatch.compute(s.as_ref(), s.as_ref());
+            (s, kss)
+        })
+        .collect();
+    from_samples(
+        data,
+        move |s: &S, t: &S| mismatch.compute(s.as_ref(), t.as_ref()),
+        normalized,
+    )
+}
+
+/// Builds a subsequence kernel matrix (optionally normalized).
+pub fn subsequence_from_strs<'a, S: AsRef<[u8]> + MaybeSync + 'a>(
+    data: Vec<S>,
+    n: usize,
+    decay: f64,
+    normalized: bool,
+) -> impl Kernel + 'a {
+    let data = data
+        .into_iter()
+        .map(|s| {
+            let kss = subsequence(s.as_ref(), s.as_ref(), n, decay);
+            (s, kss)
+        })
+        .collect();
+    from_samples(
+        data,
+        move |s: &S, t: &S| subsequence(s.as_ref(), t.as_ref(), n, decay),
+        normalized,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalized_diag_matches_rows() {
+        // the second string is shorter than k and has no k-mers at all
+        let kernel = spectrum_from_strs(vec!["abcab", "ab", "bcabc"], 3, true);
+        let full_set = vec![0, 1, 2];
+        let mut ki = vec![0.0; 3];
+        for i in 0..3 {
+            kernel.compute_row(i, &mut ki, &full_set);
+            assert_eq!(kernel.diag(i), ki[i]);
+        }
+        assert_eq!(kernel.diag(0), 1.0);
+        assert_eq!(kernel.diag(1), 0.0);
+    }
+
+    #[test]
+    fn mismatch_hand_computed() {
+        // without mismatches the kernel is the spectrum kernel (ab: 2, bc: 2, ca: 1)
+        assert_eq!(mismatch(b"abcab", b"bcabc", 2, 0, 26), 5.0);
+        assert_eq!(spectrum(b"abcab", b"bcabc", 2), 5.0);
+        // the neighborhood of every 1-mer is the whole alphabet
+        assert_eq!(mismatch(b"ab", b"c", 1, 1, 3), 6.0);
+        // binary 2-mers with one mismatch: |N(aa)| = 3, |N(aa) ∩ N(ab)| = |N(aa) ∩ N(bb)| = 2
+        assert_eq!(mismatch(b"aa", b"aa", 2, 1, 2), 3.0);
+        assert_eq!(mismatch(b"aa", b"ab", 2, 1, 2), 2.0);
+        assert_eq!(mismatch(b"aa", b"bb", 2, 1, 2), 2.0);
+        assert_eq!(mismatch(b"aab", b"ab", 2, 1, 2), 5.0);
+    }
+
+    #[test]
+    fn subsequence_hand_computed() {
+        let decay: f64 = 0.5;
+        assert_eq!(subsequence(b"ab", b"cd", 0, decay), 1.0);
+        // pairs of matching characters
+        assert_eq!(subsequence(b"aa", b"aa", 1, decay), 4.0 * decay.powi(2));
+        assert_eq!(subsequence(b"ab", b"ab", 2, decay), decay.powi(4));
+        // "ac" spans three characters of "abc" and two of "ac"
+        assert_eq!(subsequence(b"abc", b"ac", 2, decay), decay.powi(5));
+        // only "ca" is common to "cat" and "car"
+        assert_eq!(subsequence(b"cat", b"car", 2, decay), decay.powi(4));
+        assert_eq!(subsequence(b"ab", b"ab", 3, decay), 0.0);
+    }
+}
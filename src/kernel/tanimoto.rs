@@ -0,0 +1,125 @@
+//! Tanimoto (Jaccard) kernels for dense samples and binary fingerprints
+use super::linear;
+use super::{Kernel, RowKernel};
+
+/// A binary fingerprint stored as a bitset
+#[derive(Clone, Debug)]
+pub struct Bitset {
+    words: Vec<u64>,
+    count: u32,
+}
+
+impl Bitset {
+    /// Creates a [`Bitset`] of length `len` with the given bits set.
+    pub fn from_indices(len: usize, indices: &[usize]) -> Self {
+        let mut words = vec![0u64; len.div_ceil(64)];
+        for &k in indices.iter() {
+            assert!(k < len, "index should be less than the length");
+            words[k / 64] |= 1 << (k % 64);
+        }
+        Bitset::from_words(words)
+    }
+
+    /// Creates a [`Bitset`] from boolean values.
+    pub fn from_bools(bits: &[bool]) -> Self {
+        let indices: Vec<usize> = (0..bits.len()).filter(|&k| bits[k]).collect();
+        Bitset::from_indices(bits.len(), &indices)
+    }
+
+    /// Creates a [`Bitset`] from its 64-bit words (bit `k` is bit `k % 64` of word `k / 64`).
+    pub fn from_words(words: Vec<u64>) -> Self {
+        let count = words.iter().map(|w| w.count_ones()).sum();
+        Bitset { words, count }
+    }
+
+    /// Returns the number of set bits.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns the number of bits set in both bitsets.
+    pub fn count_common(&self, other: &Bitset) -> u32 {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .map(|(u, v)| (u & v).count_ones())
+            .sum()
+    }
+}
+
+/// Computes the Tanimoto kernel function `x·y / (x·x + y·y - x·y)` of dense samples.
+pub fn kernel(xi: &[f64], xj: &[f64]) -> f64 {
+    let kij = linear::kernel(xi, xj);
+    let denom = linear::kernel(xi, xi) + linear::kernel(xj, xj) - kij;
+    if denom > 0.0 {
+        kij / denom
+    } else {
+        1.0
+    }
+}
+
+/// Computes the Tanimoto kernel function `|x ∩ y| / |x ∪ y|` of bitsets.
+pub fn bits(xi: &Bitset, xj: &Bitset) -> f64 {
+    let common = xi.count_common(xj);
+    let union = xi.count + xj.count - common;
+    if union > 0 {
+        common as f64 / union as f64
+    } else {
+        1.0
+    }
+}
+
+/// Builds a Tanimoto kernel matrix for dense samples.
+pub fn from_vecs<'a>(data: Vec<&'a [f64]>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a [f64], xj: &&'a [f64]| kernel(xi, xj)),
+        Box::new(move |&_xi| 1.0),
+    )
+}
+
+/// Builds a Tanimoto kernel matrix for bitsets.
+pub fn from_bitsets<'a>(data: Vec<&'a Bitset>) -> impl Kernel + 'a {
+    RowKernel::new(
+        data,
+        Box::new(move |xi: &&'a Bitset, xj: &&'a Bitset| bits(xi, xj)),
+        Box::new(move |&_xi| 1.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dense_and_bitsets_agree() {
+        let x = Bitset::from_indices(130, &[0, 2, 3, 70]);
+        let y = Bitset::from_indices(130, &[2, 70, 100]);
+        assert_eq!(x.count(), 4);
+        assert_eq!(x.count_common(&y), 2);
+        assert_eq!(bits(&x, &y), 0.4);
+        let empty = Bitset::from_bools(&[false; 130]);
+        assert_eq!(bits(&empty, &empty), 1.0);
+
+        let fingerprints: Vec<Vec<bool>> = (0..5)
+            .map(|i| (0..130).map(|k| (k * (i + 2)) % 7 < 3).collect())
+            .collect();
+        let bitsets: Vec<Bitset> = fingerprints.iter().map(|f| Bitset::from_bools(f)).collect();
+        let dense: Vec<Vec<f64>> = fingerprints
+            .iter()
+            .map(|f| f.iter().map(|&b| if b { 1.0 } else { 0.0 }).collect())
+            .collect();
+        let bitset_kernel = from_bitsets(bitsets.iter().collect());
+        let dense_kernel = from_vecs(dense.iter().map(|d| d.as_slice()).collect());
+        let active_set: Vec<usize> = (0..5).collect();
+        let (mut ki_bits, mut ki_dense) = (vec![0.0; 5], vec![0.0; 5]);
+        for i in 0..5 {
+            bitset_kernel.compute_row(i, &mut ki_bits, &active_set);
+            dense_kernel.compute_row(i, &mut ki_dense, &active_set);
+            for (kij_bits, kij_dense) in ki_bits.iter().zip(ki_dense.iter()) {
+                assert!((kij_bits - kij_dense).abs() < 1e-14);
+            }
+            assert_eq!(ki_bits[i], 1.0);
+        }
+    }
+}
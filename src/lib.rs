@@ -6,7 +6,10 @@
 #[macro_use]
 mod console;
 
+pub mod admm;
+pub mod dcd;
 pub mod kernel;
+mod linalg;
 mod max;
 mod predict;
 pub mod problem;
@@ -14,9 +17,14 @@ pub mod smo;
 pub mod smonewt;
 pub use crate::predict::predict;
 
+pub mod incremental;
 pub mod newton;
+pub mod path;
 pub mod sensitivity;
+pub mod sgd;
 
 mod status;
 pub use crate::status::{Status, StatusCode};
+#[cfg(test)]
+mod testing;
 mod time;
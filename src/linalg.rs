@@ -0,0 +1,329 @@
+//! Small dense linear algebra helpers
+
+/// Computes the inner product of two vectors.
+pub fn dot(x: &[f64], y: &[f64]) -> f64 {
+    x.iter().zip(y).map(|(xk, yk)| xk * yk).sum()
+}
+
+/// Maximum number of sweeps of the Jacobi eigenvalue method
+const MAX_SWEEPS: usize = 100;
+
+/// Computes the eigenvalues and eigenvectors of the symmetric `n × n` matrix `a` (row major) by the cyclic Jacobi method.
+///
+/// The eigenvalues are returned in decreasing order and the kth column of the returned matrix `v` (row major) is the corresponding eigenvector.
+pub fn symmetric_eigen(mut a: Vec<f64>, n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut v = vec![0.0; n * n];
+    for i in 0..n {
+        v[i * n + i] = 1.0;
+    }
+    let norm: f64 = a.iter().map(|aij| aij * aij).sum();
+    for _sweep in 0..MAX_SWEEPS {
+        let mut off = 0.0;
+        for p in 0..n {
+            for q in p + 1..n {
+                off += a[p * n + q] * a[p * n + q];
+            }
+        }
+        if off <= f64::EPSILON * f64::EPSILON * norm {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[p * n + q];
+                if apq == 0.0 {
+                    continue;
+                }
+                let theta = (a[q * n + q] - a[p * n + p]) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[k * n + p], a[k * n + q]);
+                    a[k * n + p] = c * akp - s * akq;
+                    a[k * n + q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p * n + k], a[q * n + k]);
+                    a[p * n + k] = c * apk - s * aqk;
+                    a[q * n + k] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[k * n + p], v[k * n + q]);
+                    v[k * n + p] = c * vkp - s * vkq;
+                    v[k * n + q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    // sort eigenpairs by decreasing eigenvalue
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&k, &l| a[l * n + l].total_cmp(&a[k * n + k]));
+    let eigenvalues = order.iter().map(|&k| a[k * n + k]).collect();
+    let mut eigenvectors = vec![0.0; n * n];
+    for (idx_k, &k) in order.iter().enumerate() {
+        for i in 0..n {
+            eigenvectors[i * n + idx_k] = v[i * n + k];
+        }
+    }
+    (eigenvalues, eigenvectors)
+}
+
+/// Computes the Cholesky factor `L` (row major, lower triangular) of the symmetric positive definite `n × n` matrix `a`.
+///
+/// Returns `None` if the matrix is not (numerically) positive definite.
+pub fn cholesky(a: &[f64], n: usize) -> Option<Vec<f64>> {
+    let mut l = vec![0.0; n * n];
+    for i in 0..n {
+        for j in 0..=i {
+            let s: f64 = (0..j).map(|k| l[i * n + k] * l[j * n + k]).sum();
+            if i == j {
+                let d = a[i * n + i] - s;
+                if d <= 0.0 || !d.is_finite() {
+                    return None;
+                }
+                l[i * n + i] = d.sqrt();
+            } else {
+                l[i * n + j] = (a[i * n + j] - s) / l[j * n + j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// Solves `L Lᵀ x = b` for a Cholesky factor `L` (row major) and overwrites `b` by the solution.
+pub fn cholesky_solve(l: &[f64], n: usize, b: &mut [f64]) {
+    for i in 0..n {
+        let s: f64 = (0..i).map(|k| l[i * n + k] * b[k]).sum();
+        b[i] = (b[i] - s) / l[i * n + i];
+    }
+    for i in (0..n).rev() {
+        let s: f64 = (i + 1..n).map(|k| l[k * n + i] * b[k]).sum();
+        b[i] = (b[i] - s) / l[i * n + i];
+    }
+}
+
+/// Cholesky factor `L` of a symmetric positive definite matrix which can be updated
+/// by rank-one modifications and by the insertion and removal of rows and columns
+///
+/// The rows of the lower triangular factor are stored separately (the ith row has `i + 1` entries).
+#[derive(Clone, Debug, Default)]
+pub struct CholeskyFactor {
+    rows: Vec<Vec<f64>>,
+}
+
+impl CholeskyFactor {
+    /// Creates the factor of an empty matrix.
+    pub fn new() -> Self {
+        CholeskyFactor { rows: Vec::new() }
+    }
+
+    /// Returns the size of the matrix.
+    pub fn size(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Appends a row and column with entries `a` (the new diagonal element last) to the matrix.
+    ///
+    /// Returns `false` and keeps the factor if the enlarged matrix is not (numerically) positive definite.
+    pub fn push(&mut self, a: &[f64]) -> bool {
+        let n = self.rows.len();
+        let mut row = Vec::with_capacity(n + 1);
+        for (i, li) in self.rows.iter().enumerate() {
+            row.push((a[i] - dot(&li[..i], &row[..i])) / li[i]);
+        }
+        let d = a[n] - dot(&row, &row);
+        if d <= 0.0 || !d.is_finite() {
+            return false;
+        }
+        row.push(d.sqrt());
+        self.rows.push(row);
+        true
+    }
+
+    /// Removes the kth row and column of the matrix.
+    pub fn remove(&mut self, k: usize) {
+        let mut x: Vec<f64> = vec![0.0; self.rows.len()];
+        for (i, li) in self.rows.iter_mut().enumerate().skip(k + 1) {
+            x[i] = li.remove(k);
+        }
+        self.rows.remove(k);
+        x.remove(k);
+        // the trailing block absorbs the removed column
+        self.update_from(&mut x, 1.0, k);
+    }
+
+    /// Replaces the matrix `A` by `A + sign x xᵀ` (overwriting `x`).
+    ///
+    /// Returns `false` if a downdate (`sign < 0`) destroys positive definiteness, in which case the factor is invalid.
+    pub fn rank_one_update(&mut self, x: &mut [f64], sign: f64) -> bool {
+        let start = x.iter().position(|&xi| xi != 0.0).unwrap_or(x.len());
+        self.update_from(x, sign, start)
+    }
+
+    fn update_from(&mut self, x: &mut [f64], sign: f64, start: usize) -> bool {
+        let n = self.rows.len();
+        for k in start..n {
+            if x[k] == 0.0 {
+                continue;
+            }
+            let lkk = self.rows[k][k];
+            let r2 = lkk * lkk + sign * x[k] * x[k];
+            if r2 <= 0.0 || !r2.is_finite() {
+                return false;
+            }
+            let r = r2.sqrt();
+            let c = r / lkk;
+            let s = x[k] / lkk;
+            self.rows[k][k] = r;
+            for (li, xi) in self.rows[k + 1..].iter_mut().zip(x[k + 1..].iter_mut()) {
+                li[k] = (li[k] + sign * s * *xi) / c;
+                *xi = c * *xi - s * li[k];
+            }
+        }
+        true
+    }
+
+    /// Solves `L Lᵀ x = b` and overwrites `b` by the solution.
+    pub fn solve(&self, b: &mut [f64]) {
+        for (i, li) in self.rows.iter().enumerate() {
+            b[i] = (b[i] - dot(&li[..i], &b[..i])) / li[i];
+        }
+        for i in (0..self.rows.len()).rev() {
+            let s: f64 = (i + 1..self.rows.len())
+                .map(|k| self.rows[k][i] * b[k])
+                .sum();
+            b[i] = (b[i] - s) / self.rows[i][i];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the symmetric positive definite matrix `aᵢⱼ = exp(-(i - j)² / 8) + δᵢⱼ / 2` (row major).
+    fn spd_matrix(n: usize) -> Vec<f64> {
+        let mut a = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..n {
+                let d = i as f64 - j as f64;
+                a[i * n + j] = (-d * d / 8.0).exp() + if i == j { 0.5 } else { 0.0 };
+            }
+        }
+        a
+    }
+
+    /// Computes the factor of `a` by pushing its rows.
+    fn factorize(a: &[f64], n: usize) -> CholeskyFactor {
+        let mut factor = CholeskyFactor::new();
+        for i in 0..n {
+            assert!(factor.push(&a[i * n..i * n + i + 1]));
+        }
+        factor
+    }
+
+    /// Checks that the factor agrees with a fresh factorization of `a`.
+    fn check_factor(factor: &CholeskyFactor, a: &[f64], n: usize) {
+        assert_eq!(factor.size(), n);
+        let l = cholesky(a, n).unwrap();
+        for (i, li) in factor.rows.iter().enumerate() {
+            for (j, lij) in li.iter().enumerate() {
+                assert!((lij - l[i * n + j]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn cholesky_reproduces_matrix() {
+        let n = 6;
+        let a = spd_matrix(n);
+        let l = cholesky(&a, n).unwrap();
+        for i in 0..n {
+            for j in 0..n {
+                assert_eq!(l[i * n + j] == 0.0, j > i);
+                let lli = dot(&l[i * n..(i + 1) * n], &l[j * n..(j + 1) * n]);
+                assert!((lli - a[i * n + j]).abs() < 1e-12);
+            }
+        }
+
+        let x: Vec<f64> = (0..n).map(|i| (i as f64).sin()).collect();
+        let mut b: Vec<f64> = (0..n).map(|i| dot(&a[i * n..(i + 1) * n], &x)).collect();
+        cholesky_solve(&l, n, &mut b);
+        for (bi, xi) in b.iter().zip(x.iter()) {
+            assert!((bi - xi).abs() < 1e-12);
+        }
+
+        // indefinite and singular matrices
+        assert!(cholesky(&[1.0, 2.0, 2.0, 1.0], 2).is_none());
+        assert!(cholesky(&[1.0, 1.0, 1.0, 1.0], 2).is_none());
+    }
+
+    #[test]
+    fn symmetric_eigen_decomposes_matrix() {
+        let n = 6;
+        let a = spd_matrix(n);
+        let (eigenvalues, v) = symmetric_eigen(a.clone(), n);
+        assert!(eigenvalues.windows(2).all(|w| w[0] >= w[1]));
+        for i in 0..n {
+            for j in 0..n {
+                // V diag(eigenvalues) Vᵀ = A and Vᵀ V = I
+                let vdv: f64 = (0..n)
+                    .map(|k| v[i * n + k] * eigenvalues[k] * v[j * n + k])
+                    .sum();
+                assert!((vdv - a[i * n + j]).abs() < 1e-12);
+                let vv: f64 = (0..n).map(|k| v[k * n + i] * v[k * n + j]).sum();
+                assert!((vv - if i == j { 1.0 } else { 0.0 }).abs() < 1e-12);
+            }
+        }
+
+        let (eigenvalues, _v) = symmetric_eigen(vec![1.0, 2.0, 2.0, 1.0], 2);
+        assert!((eigenvalues[0] - 3.0).abs() < 1e-14);
+        assert!((eigenvalues[1] + 1.0).abs() < 1e-14);
+    }
+
+    #[test]
+    fn factor_push_and_remove() {
+        let n = 6;
+        let a = spd_matrix(n);
+        let mut factor = factorize(&a, n);
+        check_factor(&factor, &a, n);
+        assert!(!factor.push(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, -1.0]));
+        check_factor(&factor, &a, n);
+
+        let mut b = a;
+        for k in [n - 1, 2, 0] {
+            let m = factor.size();
+            factor.remove(k);
+            b = (0..m * m)
+                .filter(|idx| idx / m != k && idx % m != k)
+                .map(|idx| b[idx])
+                .collect();
+            check_factor(&factor, &b, m - 1);
+        }
+    }
+
+    #[test]
+    fn factor_rank_one_update() {
+        let n = 6;
+        let a = spd_matrix(n);
+        let x: Vec<f64> = (0..n)
+            .map(|i| if i < 2 { 0.0 } else { (i as f64).cos() })
+            .collect();
+        let mut b = a.clone();
+        for i in 0..n {
+            for j in 0..n {
+                b[i * n + j] += x[i] * x[j];
+            }
+        }
+        let mut factor = factorize(&a, n);
+        assert!(factor.rank_one_update(&mut x.clone(), 1.0));
+        check_factor(&factor, &b, n);
+        // the downdate restores the original matrix
+        assert!(factor.rank_one_update(&mut x.clone(), -1.0));
+        check_factor(&factor, &a, n);
+        // a downdate beyond the smallest eigenvalue fails
+        let mut y = vec![0.0; n];
+        y[3] = 2.0;
+        assert!(!factor.rank_one_update(&mut y, -1.0));
+    }
+}
@@ -1,7 +1,7 @@
 //! Newton's Method
 
 mod params;
-pub use params::Params;
+pub use params::{LinearSolver, Params};
 mod solve;
 pub use solve::{solve, solve_with_status};
 mod direction;
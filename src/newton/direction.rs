@@ -1,4 +1,6 @@
-use crate::kernel::Kernel;
+use crate::kernel::{compute_rows, Kernel};
+use crate::linalg::dot;
+use crate::newton::params::{LinearSolver, Params};
 use crate::newton::status_extended::StatusExtended;
 use crate::problem::PrimalProblem;
 
@@ -19,12 +21,217 @@ pub fn gradient(
     status_ext.dir.b = status_ext.sums.g / problem.lambda();
 }
 
+/// Solves the 2×2 system for the changes `(db, dc)` of the offset and the shift (constraints `Σ aᵢ = 0` and `Σ sᵢ aᵢ = max_asum`)
+/// given `M⁻¹ 1` and `M⁻¹ s` for the matrix `M` of the positive set.
+fn solve_constraints(
+    rhs: &[f64],
+    signs: &[f64],
+    mat_inv_one: &[f64],
+    mat_inv_signs: &[f64],
+    rhs_b: f64,
+    rhs_c: f64,
+) -> (f64, f64) {
+    let q00: f64 = mat_inv_one.iter().sum();
+    let q01: f64 = mat_inv_signs.iter().sum();
+    let q11 = dot(mat_inv_signs, signs);
+    let det = q00 * q11 - q01 * q01;
+    let p0 = dot(mat_inv_one, rhs) - rhs_b;
+    let p1 = dot(mat_inv_signs, rhs) - rhs_c;
+    let db = (q11 * p0 - q01 * p1) / det;
+    let dc = (q00 * p1 - q01 * p0) / det;
+    (db, dc)
+}
+
+/// Merges the active set and returns the signs of the positive set (vanishing if the 1-norm is not bounded).
+///
+/// Uses the gradient as direction and returns `None` if the Newton direction cannot be computed,
+/// i.e., if the positive set is empty or (with bounded 1-norm) does not contain both signs.
+fn positive_signs(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    status_ext: &mut StatusExtended,
+) -> Option<Vec<f64>> {
+    status_ext.active.merge();
+    let n_active = status_ext.active.size_positive;
+    if n_active == 0 {
+        gradient(problem, kernel, status_ext);
+        return None;
+    }
+    let mut signs = vec![0.0; n_active];
+    if problem.has_max_asum() {
+        let mut sign_pos = false;
+        let mut sign_neg = false;
+        for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
+            let si = problem.sign(i);
+            sign_pos |= si > 0.0;
+            sign_neg |= si < 0.0;
+            signs[idx_i] = si;
+        }
+        if !(sign_pos && sign_neg) {
+            gradient(problem, kernel, status_ext);
+            return None;
+        }
+    }
+    Some(signs)
+}
+
+/// Sets the Newton direction given the solutions `M⁻¹ rhs` and `M⁻¹ 1` for the matrix `M` of the positive set
+/// as well as the signs `s` with `M⁻¹ s` (only with bounded 1-norm).
+///
+/// The changes of the offset and the shift follow from the constraints (see [`solve_constraints`]).
+/// Falls back to the gradient if they are not finite.
+fn set_newton_direction(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    status_ext: &mut StatusExtended,
+    rhs: &[f64],
+    mat_inv_rhs: &[f64],
+    mat_inv_one: &[f64],
+    signs: Option<(&[f64], &[f64])>,
+) -> DirectionType {
+    let sums = &status_ext.sums;
+    let rhs_b = sums.a - sums.da_zeros;
+    let (db, dc) = match signs {
+        Some((signs, mat_inv_signs)) => {
+            // solve system with two additional constraints
+            let rhs_c = sums.sa - problem.max_asum() - sums.sda_zeros;
+            solve_constraints(rhs, signs, mat_inv_one, mat_inv_signs, rhs_b, rhs_c)
+        }
+        None => {
+            // solve system with one additional constraint
+            let sum_one: f64 = mat_inv_one.iter().sum();
+            let db = (mat_inv_rhs.iter().sum::<f64>() - rhs_b) / sum_one;
+            (db, status_ext.dir.c)
+        }
+    };
+    if !db.is_finite() || !dc.is_finite() {
+        gradient(problem, kernel, status_ext);
+        return DirectionType::Gradient;
+    }
+    status_ext.dir.b = db;
+    status_ext.dir.c = dc;
+    for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
+        let mut dai = mat_inv_rhs[idx_i] - db * mat_inv_one[idx_i];
+        if let Some((_, mat_inv_signs)) = signs {
+            dai -= dc * mat_inv_signs[idx_i];
+        }
+        status_ext.dir.a[i] = dai;
+    }
+    DirectionType::Newton
+}
+
+/// Number of kernel rows computed at once
+const ROWS_PER_BLOCK: usize = 64;
+
+/// Computes the right-hand side `H⁻¹ (a + g) - K dir / λ` of the Newton system on the positive set,
+/// where the direction is fixed for the zeros (computing only their kernel rows).
+fn compute_rhs(
+    problem: &dyn PrimalProblem,
+    kernel: &dyn Kernel,
+    status_ext: &StatusExtended,
+) -> Vec<f64> {
+    let lambda = problem.lambda();
+    let positives = status_ext.active.positives();
+    let mut rhs: Vec<f64> = positives
+        .iter()
+        .map(|&i| (status_ext.status.a[i] + status_ext.status.g[i]) / status_ext.h[i])
+        .collect();
+    let zeros = status_ext.active.zeros();
+    let mut kjs = vec![vec![0.0; positives.len()]; usize::min(ROWS_PER_BLOCK, zeros.len())];
+    for idxs in zeros.chunks(ROWS_PER_BLOCK) {
+        let kjs = &mut kjs[..idxs.len()];
+        compute_rows(kernel, idxs, kjs, positives);
+        for (&j, kj) in idxs.iter().zip(kjs.iter()) {
+            let daj = status_ext.dir.a[j];
+            for (rhs_i, kji) in rhs.iter_mut().zip(kj.iter()) {
+                *rhs_i -= daj * kji / lambda;
+            }
+        }
+    }
+    rhs
+}
+
 #[cfg(feature = "lapack")]
 mod lapack;
 #[cfg(feature = "lapack")]
-pub use lapack::newton_with_fallback;
+use lapack::newton_with_fallback as newton_dense;
 
 #[cfg(not(feature = "lapack"))]
 mod nolapack;
 #[cfg(not(feature = "lapack"))]
-pub use nolapack::newton_with_fallback;
+use nolapack::newton_with_fallback as newton_dense;
+
+mod cg;
+mod cholesky;
+mod woodbury;
+
+pub fn newton_with_fallback(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    params: &Params,
+    status_ext: &mut StatusExtended,
+) -> DirectionType {
+    match params.linear_solver {
+        LinearSolver::Direct => newton_dense(problem, kernel, status_ext),
+        LinearSolver::Cholesky => cholesky::newton_with_fallback(problem, kernel, status_ext),
+        LinearSolver::ConjugateGradient => {
+            cg::newton_with_fallback(problem, kernel, params, status_ext)
+        }
+        LinearSolver::Woodbury if kernel.factor().is_some() => {
+            woodbury::newton_with_fallback(problem, kernel, status_ext)
+        }
+        LinearSolver::Woodbury => newton_dense(problem, kernel, status_ext),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kernel::{gaussian, LowRankKernel};
+    use crate::newton::{self, LinearSolver};
+    use crate::problem::{Classification, Params};
+    use crate::testing::{classification, rows};
+
+    /// Checks that the iterates of Newton's method agree with the ones of [`LinearSolver::Direct`].
+    fn check_solver(linear_solver: LinearSolver, max_asum: f64) {
+        let (x, y) = classification(30);
+        let base = gaussian::from_vecs(rows(&x), 1.0);
+        let params = Params::new()
+            .with_lambda(0.1)
+            .with_smoothing(0.5)
+            .with_max_asum(max_asum);
+        let problem = Classification::new(&y, params);
+        for max_steps in [1, 2, 4, 100] {
+            let mut kernel = LowRankKernel::new(&base, 0.0, 30);
+            let params = newton::Params::new().with_max_steps(max_steps);
+            let reference = newton::solve(&problem, &mut kernel, &params, None).status;
+            let params = params
+                .with_linear_solver(linear_solver)
+                .with_cg_max_steps(1000)
+                .with_cg_tol(1e-14);
+            let status = newton::solve(&problem, &mut kernel, &params, None).status;
+            assert!((status.b - reference.b).abs() < 1e-6);
+            assert!((status.c - reference.c).abs() < 1e-6);
+            for (ai, ri) in status.a.iter().zip(reference.a.iter()) {
+                assert!((ai - ri).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn cholesky_agrees_with_direct() {
+        check_solver(LinearSolver::Cholesky, f64::INFINITY);
+        check_solver(LinearSolver::Cholesky, 2.0);
+    }
+
+    #[test]
+    fn conjugate_gradient_agrees_with_direct() {
+        check_solver(LinearSolver::ConjugateGradient, f64::INFINITY);
+        check_solver(LinearSolver::ConjugateGradient, 2.0);
+    }
+
+    #[test]
+    fn woodbury_agrees_with_direct() {
+        check_solver(LinearSolver::Woodbury, f64::INFINITY);
+        check_solver(LinearSolver::Woodbury, 2.0);
+    }
+}
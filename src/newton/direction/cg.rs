@@ -0,0 +1,173 @@
+use super::{
+    compute_rhs, gradient, positive_signs, set_newton_direction, DirectionType, ROWS_PER_BLOCK,
+};
+use crate::kernel::{compute_rows, Kernel};
+use crate::linalg::dot;
+use crate::newton::params::Params;
+use crate::newton::status_extended::StatusExtended;
+use crate::problem::PrimalProblem;
+
+/// Linear system `(K/λ + H⁻¹) x = b` restricted to the positive set,
+/// which is only accessed by products with rows of the kernel matrix (never stored).
+struct KernelSystem<'a> {
+    kernel: &'a dyn Kernel,
+    positives: &'a [usize],
+    h: &'a [f64],
+    lambda: f64,
+    /// Inverse diagonal of the matrix (Jacobi preconditioner)
+    precond: Vec<f64>,
+    kis: Vec<Vec<f64>>,
+}
+
+impl<'a> KernelSystem<'a> {
+    fn new(kernel: &'a dyn Kernel, positives: &'a [usize], h: &'a [f64], lambda: f64) -> Self {
+        let precond = positives
+            .iter()
+            .map(|&i| 1.0 / (kernel.diag(i) / lambda + 1.0 / h[i]))
+            .collect();
+        let kis = vec![vec![0.0; positives.len()]; usize::min(ROWS_PER_BLOCK, positives.len())];
+        KernelSystem {
+            kernel,
+            positives,
+            h,
+            lambda,
+            precond,
+            kis,
+        }
+    }
+
+    /// Multiplies the matrix with all vectors `xs` (computing each kernel row only once).
+    fn multiply(&mut self, xs: &[&[f64]]) -> Vec<Vec<f64>> {
+        let mut ys: Vec<Vec<f64>> = xs
+            .iter()
+            .map(|x| {
+                x.iter()
+                    .zip(self.positives)
+                    .map(|(xi, &i)| xi / self.h[i])
+                    .collect()
+            })
+            .collect();
+        for (block, idxs) in self.positives.chunks(ROWS_PER_BLOCK).enumerate() {
+            let kis = &mut self.kis[..idxs.len()];
+            compute_rows(self.kernel, idxs, kis, self.positives);
+            for (r, ki) in kis.iter().enumerate() {
+                let idx_i = block * ROWS_PER_BLOCK + r;
+                for (x, y) in xs.iter().zip(ys.iter_mut()) {
+                    y[idx_i] += dot(ki, x) / self.lambda;
+                }
+            }
+        }
+        ys
+    }
+
+    /// Solves the system for all right-hand sides `bs` simultaneously by the preconditioned conjugate gradient method.
+    ///
+    /// The iteration is truncated after `max_steps` steps (returning the current approximations).
+    /// Returns `None` if a direction of nonpositive curvature is encountered.
+    fn solve(&mut self, bs: &[&[f64]], max_steps: usize, tol: f64) -> Option<Vec<Vec<f64>>> {
+        let m = self.positives.len();
+        let mut xs = vec![vec![0.0; m]; bs.len()];
+        let mut rs: Vec<Vec<f64>> = bs.iter().map(|b| b.to_vec()).collect();
+        let mut ps: Vec<Vec<f64>> = rs
+            .iter()
+            .map(|r| {
+                r.iter()
+                    .zip(self.precond.iter())
+                    .map(|(ri, di)| ri * di)
+                    .collect()
+            })
+            .collect();
+        let mut rzs: Vec<f64> = rs.iter().zip(ps.iter()).map(|(r, p)| dot(r, p)).collect();
+        let thresholds: Vec<f64> = bs.iter().map(|b| tol * dot(b, b).sqrt()).collect();
+        for _step in 0..max_steps {
+            let unconverged: Vec<usize> = (0..bs.len())
+                .filter(|&l| dot(&rs[l], &rs[l]).sqrt() > thresholds[l])
+                .collect();
+            if unconverged.is_empty() {
+                break;
+            }
+            let qs = self.multiply(
+                &unconverged
+                    .iter()
+                    .map(|&l| ps[l].as_slice())
+                    .collect::<Vec<_>>(),
+            );
+            for (&l, q) in unconverged.iter().zip(qs.iter()) {
+                let pq = dot(&ps[l], q);
+                if pq <= 0.0 || !pq.is_finite() {
+                    return None;
+                }
+                let alpha = rzs[l] / pq;
+                for ((xi, ri), (pi, qi)) in xs[l]
+                    .iter_mut()
+                    .zip(rs[l].iter_mut())
+                    .zip(ps[l].iter().zip(q.iter()))
+                {
+                    *xi += alpha * pi;
+                    *ri -= alpha * qi;
+                }
+                let z: Vec<f64> = rs[l]
+                    .iter()
+                    .zip(self.precond.iter())
+                    .map(|(ri, di)| ri * di)
+                    .collect();
+                let rz = dot(&rs[l], &z);
+                let beta = rz / rzs[l];
+                rzs[l] = rz;
+                for (pi, zi) in ps[l].iter_mut().zip(z) {
+                    *pi = zi + beta * *pi;
+                }
+            }
+        }
+        Some(xs)
+    }
+}
+
+/// Computes an (approximate) Newton direction by the matrix-free preconditioned conjugate gradient method (truncated Newton).
+pub fn newton_with_fallback(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    params: &Params,
+    status_ext: &mut StatusExtended,
+) -> DirectionType {
+    let Some(signs) = positive_signs(problem, kernel, status_ext) else {
+        return DirectionType::Gradient;
+    };
+    let n_active = signs.len();
+    let lambda = problem.lambda();
+
+    let rhs = compute_rhs(problem, kernel, status_ext);
+
+    let ones = vec![1.0; n_active];
+    let mut bs: Vec<&[f64]> = vec![&rhs, &ones];
+    if problem.has_max_asum() {
+        bs.push(&signs);
+    }
+    let mut system = KernelSystem::new(
+        &*kernel,
+        status_ext.active.positives(),
+        &status_ext.h,
+        lambda,
+    );
+    let solutions = match system.solve(&bs, params.cg_max_steps, params.cg_tol) {
+        Some(solutions) => solutions,
+        None => {
+            gradient(problem, kernel, status_ext);
+            return DirectionType::Gradient;
+        }
+    };
+    let signs = if problem.has_max_asum() {
+        Some((signs.as_slice(), solutions[2].as_slice()))
+    } else {
+        None
+    };
+    set_newton_direction(
+        problem,
+        kernel,
+        status_ext,
+        &rhs,
+        &solutions[0],
+        &solutions[1],
+        signs,
+    )
+}
@@ -0,0 +1,171 @@
+use super::{
+    compute_rhs, gradient, positive_signs, set_newton_direction, DirectionType, ROWS_PER_BLOCK,
+};
+use crate::kernel::{compute_rows, Kernel};
+use crate::linalg::CholeskyFactor;
+use crate::newton::status_extended::{Factorization, StatusExtended};
+use crate::problem::PrimalProblem;
+
+/// Maximum number of changed indices (relative to the size of the positive set) for which the factorization is updated instead of recomputed
+const MAX_UPDATE_FRACTION: f64 = 0.2;
+
+impl Factorization {
+    /// Creates the factorization of an empty matrix.
+    fn new(lambda: f64) -> Self {
+        Factorization {
+            indices: Vec::new(),
+            diag: Vec::new(),
+            lambda,
+            chol: CholeskyFactor::new(),
+        }
+    }
+
+    /// Returns the number of rank-one modifications, removals and insertions needed to obtain the factorization for `positives`.
+    fn num_changes(&self, positives: &[usize], h: &[f64], in_positives: &[bool]) -> usize {
+        let mut in_factor = vec![false; in_positives.len()];
+        let mut changes = 0;
+        for (&i, &d) in self.indices.iter().zip(self.diag.iter()) {
+            in_factor[i] = true;
+            if !in_positives[i] || d != 1.0 / h[i] {
+                changes += 1;
+            }
+        }
+        changes + positives.iter().filter(|&&i| !in_factor[i]).count()
+    }
+
+    /// Updates the factorization to the set `positives` by removing, modifying and appending rows and columns.
+    ///
+    /// Returns `false` if the matrix is not (numerically) positive definite, in which case the factorization is invalid.
+    fn update(
+        &mut self,
+        kernel: &dyn Kernel,
+        positives: &[usize],
+        h: &[f64],
+        in_positives: &[bool],
+    ) -> bool {
+        // remove indices (starting from the end to keep the positions valid)
+        for k in (0..self.indices.len()).rev() {
+            if !in_positives[self.indices[k]] {
+                self.chol.remove(k);
+                self.indices.remove(k);
+                self.diag.remove(k);
+            }
+        }
+
+        // modify diagonal entries by rank-one updates and downdates
+        let mut in_factor = vec![false; in_positives.len()];
+        for k in 0..self.indices.len() {
+            let i = self.indices[k];
+            in_factor[i] = true;
+            let delta = 1.0 / h[i] - self.diag[k];
+            if delta == 0.0 {
+                continue;
+            }
+            let mut x = vec![0.0; self.indices.len()];
+            x[k] = delta.abs().sqrt();
+            if !self.chol.rank_one_update(&mut x, delta.signum()) {
+                return false;
+            }
+            self.diag[k] = 1.0 / h[i];
+        }
+
+        // append new indices
+        let added: Vec<usize> = positives
+            .iter()
+            .copied()
+            .filter(|&i| !in_factor[i])
+            .collect();
+        let mut all = self.indices.clone();
+        all.extend_from_slice(&added);
+        let mut kis = vec![vec![0.0; all.len()]; usize::min(ROWS_PER_BLOCK, added.len())];
+        for idxs in added.chunks(ROWS_PER_BLOCK) {
+            let kis = &mut kis[..idxs.len()];
+            compute_rows(kernel, idxs, kis, &all);
+            for (&i, ki) in idxs.iter().zip(kis.iter_mut()) {
+                let m = self.indices.len();
+                let col = &mut ki[..=m];
+                col.iter_mut().for_each(|kij| *kij /= self.lambda);
+                col[m] += 1.0 / h[i];
+                if !self.chol.push(col) {
+                    return false;
+                }
+                self.indices.push(i);
+                self.diag.push(1.0 / h[i]);
+            }
+        }
+        true
+    }
+}
+
+/// Computes the Newton direction using a Cholesky factorization which is kept between the steps
+/// and only updated if the positive set and the second loss derivatives change slightly.
+pub fn newton_with_fallback(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    status_ext: &mut StatusExtended,
+) -> DirectionType {
+    let Some(signs) = positive_signs(problem, kernel, status_ext) else {
+        return DirectionType::Gradient;
+    };
+    let n_active = signs.len();
+    let lambda = problem.lambda();
+
+    // update or recompute the factorization
+    let positives = status_ext.active.positives();
+    let h = &status_ext.h;
+    let mut in_positives = vec![false; problem.size()];
+    for &i in positives.iter() {
+        in_positives[i] = true;
+    }
+    let mut factorization = match status_ext.factorization.take() {
+        Some(factorization)
+            if factorization.lambda == lambda
+                && factorization.num_changes(positives, h, &in_positives) as f64
+                    <= MAX_UPDATE_FRACTION * n_active as f64 =>
+        {
+            factorization
+        }
+        _ => Factorization::new(lambda),
+    };
+    if !factorization.update(&*kernel, positives, h, &in_positives) {
+        // recompute from scratch to get rid of accumulated rounding errors
+        factorization = Factorization::new(lambda);
+        if !factorization.update(&*kernel, positives, h, &in_positives) {
+            gradient(problem, kernel, status_ext);
+            return DirectionType::Gradient;
+        }
+    }
+
+    // positions of the positive set within the factor
+    let mut position = vec![0; problem.size()];
+    for (k, &i) in factorization.indices.iter().enumerate() {
+        position[i] = k;
+    }
+    let solve = |b: &[f64]| -> Vec<f64> {
+        let mut x = vec![0.0; n_active];
+        for (&i, bi) in positives.iter().zip(b) {
+            x[position[i]] = *bi;
+        }
+        factorization.chol.solve(&mut x);
+        positives.iter().map(|&i| x[position[i]]).collect()
+    };
+
+    let rhs = compute_rhs(problem, kernel, status_ext);
+    let mat_inv_rhs = solve(&rhs);
+    let mat_inv_one = solve(&vec![1.0; n_active]);
+
+    let mat_inv_signs = problem.has_max_asum().then(|| solve(&signs));
+    // the factorization is kept even if the direction falls back to the gradient
+    status_ext.factorization = Some(factorization);
+    set_newton_direction(
+        problem,
+        kernel,
+        status_ext,
+        &rhs,
+        &mat_inv_rhs,
+        &mat_inv_one,
+        mat_inv_signs
+            .as_deref()
+            .map(|mat_inv_signs| (signs.as_slice(), mat_inv_signs)),
+    )
+}
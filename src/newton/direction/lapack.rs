@@ -1,4 +1,4 @@
-use super::{gradient, DirectionType};
+use super::{positive_signs, set_newton_direction, DirectionType};
 use crate::kernel::Kernel;
 use crate::newton::status_extended::StatusExtended;
 use crate::problem::PrimalProblem;
@@ -38,59 +38,27 @@ pub fn newton_with_fallback(
     kernel: &mut dyn Kernel,
     status_ext: &mut StatusExtended,
 ) -> DirectionType {
-    status_ext.active.merge();
-    let n_active = status_ext.active.size_positive;
-    if n_active == 0 {
-        gradient(problem, kernel, status_ext);
+    let Some(signs) = positive_signs(problem, kernel, status_ext) else {
         return DirectionType::Gradient;
-    }
-    let mut signs = Array::zeros((n_active,));
-    if problem.has_max_asum() {
-        let mut sign_pos = false;
-        let mut sign_neg = false;
-        for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
-            let si = problem.sign(i);
-            sign_pos |= si > 0.0;
-            sign_neg |= si < 0.0;
-            signs[idx_i] = si;
-        }
-        if !(sign_pos && sign_neg) {
-            gradient(problem, kernel, status_ext);
-            return DirectionType::Gradient;
-        }
-    }
+    };
+    let n_active = signs.len();
     let (mat, rhs) = compute_matrix_and_rhs(problem, kernel, status_ext);
     let mat_fact = mat.factorize_into().unwrap();
     let mat_inv_rhs = mat_fact.solve(&rhs).unwrap();
     let mat_inv_one = mat_fact.solve_into(Array::ones((n_active,))).unwrap();
 
-    let sums = &status_ext.sums;
-    let rhs_b = sums.a - sums.da_zeros;
-    let da_nonzero = if problem.has_max_asum() {
-        // solve system with two additional constraints
-        let rhs_c = sums.sa - problem.max_asum() - sums.sda_zeros;
-        let mat_inv_signs = mat_fact.solve(&signs).unwrap();
-        // create and solve 2x2 system
-        let q00 = mat_inv_one.sum();
-        let q01 = mat_inv_signs.sum();
-        let q11 = mat_inv_signs.dot(&signs);
-        let det = q00 * q11 - q01 * q01;
-        let p0 = mat_inv_one.dot(&rhs) - rhs_b;
-        let p1 = mat_inv_signs.dot(&rhs) - rhs_c;
-        // extract solution for scalar variables
-        let db = (q11 * p0 - q01 * p1) / det;
-        status_ext.dir.b = db;
-        let dc = (q00 * p1 - q01 * p0) / det;
-        status_ext.dir.c = dc;
-        mat_inv_rhs - db * mat_inv_one - dc * mat_inv_signs
-    } else {
-        // solve system with one additional constraints
-        let db = (mat_inv_rhs.sum() - rhs_b) / mat_inv_one.sum();
-        status_ext.dir.b = db;
-        mat_inv_rhs - db * mat_inv_one
-    };
-    for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
-        status_ext.dir.a[i] = da_nonzero[idx_i];
-    }
-    return DirectionType::Newton;
+    let mat_inv_signs = problem
+        .has_max_asum()
+        .then(|| mat_fact.solve_into(Array::from(signs.clone())).unwrap());
+    set_newton_direction(
+        problem,
+        kernel,
+        status_ext,
+        rhs.as_slice().unwrap(),
+        mat_inv_rhs.as_slice().unwrap(),
+        mat_inv_one.as_slice().unwrap(),
+        mat_inv_signs
+            .as_ref()
+            .map(|mat_inv_signs| (signs.as_slice(), mat_inv_signs.as_slice().unwrap())),
+    )
 }
@@ -0,0 +1,126 @@
+use super::{gradient, positive_signs, set_newton_direction, DirectionType};
+use crate::kernel::{Factor, Kernel};
+use crate::linalg::{cholesky, cholesky_solve, dot};
+use crate::newton::status_extended::StatusExtended;
+use crate::problem::PrimalProblem;
+
+/// Linear system `(K/λ + H⁻¹) x = b` restricted to the positive set with `K = G Gᵀ`,
+/// which is solved by the Woodbury identity using a `rank × rank` Cholesky factorization.
+struct LowRankSystem<'a> {
+    factor: &'a Factor<'a>,
+    positives: &'a [usize],
+    h: &'a [f64],
+    scale: f64,
+    chol: Vec<f64>,
+}
+
+impl<'a> LowRankSystem<'a> {
+    fn new(factor: &'a Factor, positives: &'a [usize], h: &'a [f64], lambda: f64) -> Option<Self> {
+        let rank = factor.rank();
+        // build I + Uᵀ H U with U = G / sqrt(λ)
+        let mut mat = vec![0.0; rank * rank];
+        for &i in positives.iter() {
+            let gi = factor.row(i);
+            for r in 0..rank {
+                for s in 0..=r {
+                    mat[r * rank + s] += h[i] * gi[r] * gi[s] / lambda;
+                }
+            }
+        }
+        for r in 0..rank {
+            mat[r * rank + r] += 1.0;
+            for s in 0..r {
+                mat[s * rank + r] = mat[r * rank + s];
+            }
+        }
+        let chol = cholesky(&mat, rank)?;
+        Some(LowRankSystem {
+            factor,
+            positives,
+            h,
+            scale: lambda.sqrt(),
+            chol,
+        })
+    }
+
+    fn solve(&self, b: &[f64]) -> Vec<f64> {
+        let rank = self.factor.rank();
+        let hb: Vec<f64> = self
+            .positives
+            .iter()
+            .zip(b)
+            .map(|(&i, bi)| self.h[i] * bi)
+            .collect();
+        let mut t = vec![0.0; rank];
+        for (&i, hbi) in self.positives.iter().zip(hb.iter()) {
+            for (tr, gir) in t.iter_mut().zip(self.factor.row(i)) {
+                *tr += gir * hbi / self.scale;
+            }
+        }
+        cholesky_solve(&self.chol, rank, &mut t);
+        self.positives
+            .iter()
+            .zip(hb)
+            .map(|(&i, hbi)| hbi - self.h[i] * dot(self.factor.row(i), &t) / self.scale)
+            .collect()
+    }
+}
+
+/// Computes the Newton direction for a kernel matrix available in low-rank form (see [`Kernel::factor`]).
+pub fn newton_with_fallback(
+    problem: &dyn PrimalProblem,
+    kernel: &mut dyn Kernel,
+    status_ext: &mut StatusExtended,
+) -> DirectionType {
+    let Some(signs) = positive_signs(problem, kernel, status_ext) else {
+        return DirectionType::Gradient;
+    };
+    let n_active = signs.len();
+    let lambda = problem.lambda();
+    let factor = kernel.factor().unwrap();
+    let system = match LowRankSystem::new(
+        &factor,
+        status_ext.active.positives(),
+        &status_ext.h,
+        lambda,
+    ) {
+        Some(system) => system,
+        None => {
+            gradient(problem, kernel, status_ext);
+            return DirectionType::Gradient;
+        }
+    };
+
+    // compute right-hand side using G (Σ dir_j g_j) for the contribution of the zeros
+    let mut gz = vec![0.0; factor.rank()];
+    for &j in status_ext.active.zeros().iter() {
+        for (gzr, gjr) in gz.iter_mut().zip(factor.row(j)) {
+            *gzr += status_ext.dir.a[j] * gjr;
+        }
+    }
+    let rhs: Vec<f64> = status_ext
+        .active
+        .positives()
+        .iter()
+        .map(|&i| {
+            (status_ext.status.a[i] + status_ext.status.g[i]) / status_ext.h[i]
+                - dot(factor.row(i), &gz) / lambda
+        })
+        .collect();
+
+    let mat_inv_rhs = system.solve(&rhs);
+    let mat_inv_one = system.solve(&vec![1.0; n_active]);
+
+    let mat_inv_signs = problem.has_max_asum().then(|| system.solve(&signs));
+    set_newton_direction(
+        problem,
+        kernel,
+        status_ext,
+        &rhs,
+        &mat_inv_rhs,
+        &mat_inv_one,
+        mat_inv_signs
+            .as_deref()
+            .map(|mat_inv_signs| (signs.as_slice(), mat_inv_signs)),
+    )
+}
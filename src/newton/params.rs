@@ -1,5 +1,19 @@
 use serde::{Deserialize, Serialize};
 
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// Method for solving the linear system of the Newton direction
+pub enum LinearSolver {
+    /// Dense factorization of the `n_active × n_active` matrix
+    Direct,
+    /// Cholesky factorization, which is updated (instead of recomputed) if the positive set and the second loss derivatives change only slightly
+    Cholesky,
+    /// Matrix-free preconditioned conjugate gradient method (truncated Newton) using only kernel rows
+    ConjugateGradient,
+    /// Woodbury identity with a `rank × rank` factorization for kernel matrices given in low-rank form (see [`Kernel::factor`](crate::kernel::Kernel::factor)),
+    /// falls back to [`LinearSolver::Direct`] for other kernel matrices
+    Woodbury,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 /// Parameters of Newton's method
 pub struct Params {
@@ -17,6 +31,12 @@ pub struct Params {
     pub eta: f64,
     /// Maximum number of steps in Armijo stepsize selection
     pub max_back_steps: usize,
+    /// Method for solving the linear system of the Newton direction
+    pub linear_solver: LinearSolver,
+    /// Maximum number of conjugate gradient steps per Newton direction
+    pub cg_max_steps: usize,
+    /// Relative residual tolerance of the conjugate gradient method
+    pub cg_tol: f64,
 }
 
 impl Params {
@@ -30,6 +50,9 @@ impl Params {
             sigma: 0.001,
             eta: 0.1,
             max_back_steps: 8,
+            linear_solver: LinearSolver::Direct,
+            cg_max_steps: 100,
+            cg_tol: 1e-8,
         }
     }
 
@@ -56,4 +79,22 @@ impl Params {
         self.max_steps = max_steps;
         self
     }
+
+    /// Updates the method for solving the linear system of the Newton direction.
+    pub fn with_linear_solver(mut self, linear_solver: LinearSolver) -> Self {
+        self.linear_solver = linear_solver;
+        self
+    }
+
+    /// Updates the maximum number of conjugate gradient steps.
+    pub fn with_cg_max_steps(mut self, cg_max_steps: usize) -> Self {
+        self.cg_max_steps = cg_max_steps;
+        self
+    }
+
+    /// Updates the relative residual tolerance of the conjugate gradient method.
+    pub fn with_cg_tol(mut self, cg_tol: f64) -> Self {
+        self.cg_tol = cg_tol;
+        self
+    }
 }
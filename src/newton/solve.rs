@@ -106,7 +106,7 @@ fn descent_step(
 ) -> (DirectionType, f64, usize) {
     // compute Newton or gradient direction
     let mut direction_type = if try_newton {
-        super::direction::newton_with_fallback(problem, kernel, status_ext)
+        super::direction::newton_with_fallback(problem, kernel, params, status_ext)
     } else {
         super::direction::gradient(problem, kernel, status_ext);
         DirectionType::Gradient
@@ -159,6 +159,7 @@ pub fn solve_with_status(
         sums: Sums::new(),
         h: vec![0.0; n],
         ki: vec![0.0; n],
+        factorization: None,
     };
 
     let obj_primal = problem.objective(&status);
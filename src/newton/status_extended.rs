@@ -1,3 +1,4 @@
+use crate::linalg::CholeskyFactor;
 use crate::Status;
 
 pub struct ActiveSet {
@@ -78,6 +79,18 @@ impl Sums {
     }
 }
 
+/// Cholesky factorization of `K/λ + H⁻¹` restricted to a set of indices, which is kept and updated between the steps
+pub struct Factorization {
+    /// Indices in the order of the rows of the factor
+    pub indices: Vec<usize>,
+    /// Diagonal entries `1/h_i` used for the factorization
+    pub diag: Vec<f64>,
+    /// Regularization parameter used for the factorization
+    pub lambda: f64,
+    /// Cholesky factor
+    pub chol: CholeskyFactor,
+}
+
 /// Status with additions (mainly second-order) information
 pub struct StatusExtended {
     /// Underlying base status
@@ -92,4 +105,6 @@ pub struct StatusExtended {
     pub h: Vec<f64>,
     /// Place to store the current kernel matrix row
     pub ki: Vec<f64>,
+    /// Factorization from the previous step (only used by [`LinearSolver::Cholesky`](crate::newton::LinearSolver::Cholesky))
+    pub factorization: Option<Factorization>,
 }
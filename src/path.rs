@@ -0,0 +1,155 @@
+//! Solutions along a sequence of regularization parameters λ
+use crate::kernel::Kernel;
+use crate::newton;
+use crate::problem::base::ProblemBase;
+use crate::problem::{DualProblem, Problem, Regularized};
+use crate::smo;
+use crate::status::Status;
+use crate::time::{now, until_now};
+
+pub mod hinge;
+
+/// Solver used for every value of λ
+#[derive(Clone, Debug)]
+pub enum Solver {
+    /// SMO method (see [`smo::solve_with_status`])
+    Smo(smo::Params),
+    /// Newton's method (see [`newton::solve_with_status`])
+    Newton(newton::Params),
+}
+
+/// Solution for a single value of λ
+#[derive(Clone, Debug)]
+pub struct PathPoint {
+    /// Regularization parameter λ
+    pub lambda: f64,
+    /// Final status of the solver
+    pub status: Status,
+    /// Elapsed time for this value of λ (in seconds) including the warm start
+    pub time: f64,
+}
+
+/// Solutions along a sequence of regularization parameters
+#[derive(Clone, Debug)]
+pub struct Path {
+    /// Solutions in the order of the given values of λ
+    pub points: Vec<PathPoint>,
+    /// Total elapsed time (in seconds)
+    pub time: f64,
+}
+
+/// Adapts a status computed for `lambda_old` as starting point for `lambda_new`.
+///
+/// The coefficients `a`, the offset `b` and the shift `c` are kept,
+/// whereas the product `ka` (scaled by λ⁻¹) is rescaled and the dual objective function value is recomputed.
+pub fn rescale(problem: &dyn DualProblem, status: &mut Status, lambda_old: f64, lambda_new: f64) {
+    let factor = lambda_old / lambda_new;
+    for kai in status.ka.iter_mut() {
+        *kai *= factor;
+    }
+    status.value = -problem.objective(status);
+}
+
+/// Solves the training problem for each of the (sorted) values in `lambdas`, warm-started by the previous solution.
+///
+/// The regularization parameter of the problem itself is ignored.
+/// Starting with the largest value of λ is typically the cheapest order.
+pub fn solve(
+    problem: &dyn Problem,
+    kernel: &mut dyn Kernel,
+    lambdas: &[f64],
+    solver: &Solver,
+    callback: Option<&dyn Fn(&Status) -> bool>,
+) -> Path {
+    assert!(
+        lambdas.windows(2).all(|w| w[0] >= w[1]) || lambdas.windows(2).all(|w| w[0] <= w[1]),
+        "values of lambda should be sorted"
+    );
+    assert!(
+        lambdas.iter().all(|&lambda| lambda > 0.0),
+        "values of lambda should be positive"
+    );
+    let start = now();
+    let mut points: Vec<PathPoint> = Vec::with_capacity(lambdas.len());
+    for &lambda in lambdas.iter() {
+        let start_point = now();
+        let problem = Regularized::new(problem, lambda);
+        let status = match points.last() {
+            Some(previous) => {
+                let mut status = previous.status.clone();
+                rescale(&problem, &mut status, previous.lambda, lambda);
+                status
+            }
+            None => {
+                let mut status = Status::new(problem.size());
+                for k in 0..problem.size() {
+                    status.value -= problem.dloss(k, 0.0);
+                }
+                status
+            }
+        };
+        let status = match solver {
+            Solver::Smo(params) => {
+                smo::solve_with_status(status, &problem, kernel, params, callback)
+            }
+            Solver::Newton(params) => {
+                newton::solve_with_status(status, &problem, kernel, params, callback).status
+            }
+        };
+        points.push(PathPoint {
+            lambda,
+            status,
+            time: until_now(start_point),
+        });
+    }
+    Path {
+        points,
+        time: until_now(start),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{Classification, Params as ProblemParams, LSSVM};
+    use crate::testing::{classification, rows};
+
+    /// Compares the path with solutions computed from scratch for each value of λ.
+    fn check_path(problem: &dyn Problem, kernel: &mut dyn Kernel, solver: &Solver) {
+        for lambdas in [[1.0, 0.3, 0.1, 0.03], [0.03, 0.1, 0.3, 1.0]] {
+            let path = solve(problem, kernel, &lambdas, solver, None);
+            assert_eq!(path.points.len(), lambdas.len());
+            for point in path.points.iter() {
+                let regularized = Regularized::new(problem, point.lambda);
+                assert_eq!(regularized.lambda(), point.lambda);
+                let reference = match solver {
+                    Solver::Smo(params) => smo::solve(&regularized, kernel, params, None),
+                    Solver::Newton(params) => {
+                        newton::solve(&regularized, kernel, params, None).status
+                    }
+                };
+                let value = point.status.value;
+                assert!((value - reference.value).abs() < 1e-6 * (1.0 + reference.value.abs()));
+            }
+        }
+    }
+
+    #[test]
+    fn smo_path_agrees_with_cold_starts() {
+        let (x, y) = classification(30);
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let problem = Classification::new(&y, ProblemParams::new());
+        let solver = Solver::Smo(smo::Params::new().with_tol(1e-10));
+        check_path(&problem, &mut kernel, &solver);
+    }
+
+    #[test]
+    fn newton_path_agrees_with_cold_starts() {
+        let (x, y) = classification(30);
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let problem = LSSVM::new(&y, ProblemParams::new());
+        let solver = Solver::Newton(newton::Params::new().with_tol(1e-10));
+        check_path(&problem, &mut kernel, &solver);
+    }
+}
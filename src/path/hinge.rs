@@ -0,0 +1,477 @@
+//! Exact regularization path of the hinge loss SVM (Hastie et al., 2004)
+//!
+//! For the [`Classification`] problem without smoothing, the coefficients `a` and the product `b λ` are piecewise linear in λ.
+//! The path is traced from the largest relevant value of λ downwards by tracking the samples on the margin (elbow set).
+use crate::kernel::Kernel;
+use crate::problem::base::ProblemBase;
+use crate::problem::Classification;
+use crate::status::Status;
+
+use rulinalg::matrix::decomposition::PartialPivLu;
+use rulinalg::matrix::Matrix;
+use rulinalg::vector::Vector;
+
+/// Parameters of the path computation
+#[derive(Clone, Debug)]
+pub struct Params {
+    /// Smallest value of λ of interest
+    pub lambda_min: f64,
+    /// Maximum number of breakpoints
+    pub max_steps: usize,
+    /// Tolerance for the initial subproblem and the detection of events
+    pub tol: f64,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            lambda_min: 0.0,
+            max_steps: usize::MAX,
+            tol: 1e-10,
+        }
+    }
+
+    /// Updates the smallest value of λ.
+    pub fn with_lambda_min(mut self, lambda_min: f64) -> Self {
+        self.lambda_min = lambda_min;
+        self
+    }
+
+    /// Updates the maximum number of breakpoints.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Updates the tolerance.
+    pub fn with_tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Event happening at a breakpoint
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Start of the path (all larger values of λ share the same coefficients)
+    Initial,
+    /// Sample enters the elbow set (`yᵢ tᵢ = shift`).
+    Enter(usize),
+    /// Sample leaves the elbow set with coefficient at its bound (`yᵢ tᵢ < shift`).
+    LeaveLeft(usize),
+    /// Sample leaves the elbow set with vanishing coefficient (`yᵢ tᵢ > shift`).
+    LeaveRight(usize),
+    /// End of the path at [`Params::lambda_min`]
+    Final,
+}
+
+/// Reason for the end of the path
+#[derive(Clone, Debug, PartialEq)]
+pub enum Termination {
+    /// No sample violates the margin anymore (the data is separable).
+    Separable,
+    /// The smallest value of λ is reached.
+    LambdaMin,
+    /// The maximum number of breakpoints is reached.
+    MaxSteps,
+    /// The linear system of the elbow set is singular.
+    Singular,
+}
+
+/// Breakpoint of the piecewise linear path
+#[derive(Clone, Debug)]
+pub struct Breakpoint {
+    /// Regularization parameter λ
+    pub lambda: f64,
+    /// Offset of the decision function
+    pub b: f64,
+    /// Samples in the elbow set (after the event)
+    pub elbow: Vec<usize>,
+    /// Coefficients of the samples in the elbow set
+    pub elbow_a: Vec<f64>,
+    /// Event at this breakpoint
+    pub event: Event,
+}
+
+/// Exact solution path of the hinge loss SVM
+#[derive(Clone, Debug)]
+pub struct HingePath {
+    /// Breakpoints with decreasing values of λ
+    pub breakpoints: Vec<Breakpoint>,
+    /// Reason for the end of the path
+    pub termination: Termination,
+    /// Coefficients at the first breakpoint
+    initial_a: Vec<f64>,
+    /// Slope of `b λ` for values of λ above the first breakpoint
+    initial_slope: f64,
+    labels: Vec<f64>,
+}
+
+impl HingePath {
+    /// Returns the values of λ at the breakpoints.
+    pub fn lambdas(&self) -> Vec<f64> {
+        self.breakpoints.iter().map(|bp| bp.lambda).collect()
+    }
+
+    /// Applies the changes of the kth breakpoint to the coefficients of the previous one.
+    fn apply(&self, a: &mut [f64], k: usize) {
+        let bp = &self.breakpoints[k];
+        match bp.event {
+            Event::LeaveLeft(i) => a[i] = self.labels[i],
+            Event::LeaveRight(i) => a[i] = 0.0,
+            _ => {}
+        }
+        for (&i, &ai) in bp.elbow.iter().zip(bp.elbow_a.iter()) {
+            a[i] = ai;
+        }
+    }
+
+    /// Computes the coefficients `a` and the offset `b` for a particular value of λ.
+    ///
+    /// Values of λ below the last breakpoint use the coefficients of the last breakpoint.
+    pub fn coefficients(&self, lambda: f64) -> (Vec<f64>, f64) {
+        let mut a = self.initial_a.clone();
+        let first = &self.breakpoints[0];
+        if lambda >= first.lambda {
+            let offset = first.b * first.lambda + self.initial_slope * (lambda - first.lambda);
+            return (a, offset / lambda);
+        }
+        for k in 1..self.breakpoints.len() {
+            let (prev, next) = (&self.breakpoints[k - 1], &self.breakpoints[k]);
+            if lambda >= next.lambda {
+                let a_prev = a.clone();
+                self.apply(&mut a, k);
+                let theta = (prev.lambda - lambda) / (prev.lambda - next.lambda);
+                for (ai, ai_prev) in a.iter_mut().zip(a_prev.iter()) {
+                    *ai = ai_prev + theta * (*ai - ai_prev);
+                }
+                let offset =
+                    prev.b * prev.lambda + theta * (next.b * next.lambda - prev.b * prev.lambda);
+                return (a, offset / lambda);
+            }
+            self.apply(&mut a, k);
+        }
+        let last = self.breakpoints.last().unwrap();
+        (a, last.b)
+    }
+
+    /// Computes the [`Status`] (coefficients, offset and kernel product) for a particular value of λ.
+    pub fn status(&self, lambda: f64, kernel: &mut dyn Kernel) -> Status {
+        let (a, b) = self.coefficients(lambda);
+        let n = a.len();
+        let mut status = Status::new(n);
+        let full_set: Vec<usize> = (0..n).collect();
+        for (i, &ai) in a.iter().enumerate() {
+            if ai == 0.0 {
+                continue;
+            }
+            kernel.use_rows(&[i], &full_set, &mut |kis| {
+                for (kak, kik) in status.ka.iter_mut().zip(kis[0].iter()) {
+                    *kak += ai / lambda * kik;
+                }
+            });
+        }
+        status.asum = a
+            .iter()
+            .zip(self.labels.iter())
+            .map(|(ai, yi)| ai * yi)
+            .sum();
+        status.a = a;
+        status.b = b;
+        status
+    }
+}
+
+/// Adds `factor` times the jth row of the kernel matrix to `v`.
+fn add_row(kernel: &mut dyn Kernel, j: usize, factor: f64, v: &mut [f64], full_set: &[usize]) {
+    kernel.use_rows(&[j], full_set, &mut |kjs| {
+        for (vk, kjk) in v.iter_mut().zip(kjs[0].iter()) {
+            *vk += factor * kjk;
+        }
+    });
+}
+
+/// Computes the exact regularization path of the [`Classification`] problem (which should not use smoothing).
+///
+/// The computation works with `α = y a ∈ [0, 1]` and `h = K (y α)` in a scaled parameter `λ' = shift · λ`.
+pub fn solve(problem: &Classification, kernel: &mut dyn Kernel, params: &Params) -> HingePath {
+    assert!(problem.smoothing() == 0.0, "smoothing should be zero");
+    assert!(!problem.has_max_asum(), "max_asum is not supported");
+    assert!(problem.shift > 0.0, "shift should be positive");
+    let shift = problem.shift;
+    let n = problem.size();
+    let y: Vec<f64> = (0..n).map(|i| problem.sign(i)).collect();
+    let full_set: Vec<usize> = (0..n).collect();
+    let n_pos = y.iter().filter(|&&yi| yi > 0.0).count();
+    let n_neg = n - n_pos;
+    assert!(n_pos > 0 && n_neg > 0, "both classes should be present");
+
+    // all samples of the minority class start with α = 1
+    let (ys, n_s, n_m) = if n_pos <= n_neg {
+        (1.0, n_pos, n_neg)
+    } else {
+        (-1.0, n_neg, n_pos)
+    };
+    let ym = -ys;
+    let mut alpha: Vec<f64> = y
+        .iter()
+        .map(|&yi| {
+            if yi == ys {
+                1.0
+            } else {
+                n_s as f64 / n_m as f64
+            }
+        })
+        .collect();
+    let mut h = vec![0.0; n];
+    for j in 0..n {
+        add_row(kernel, j, y[j] * alpha[j], &mut h, &full_set);
+    }
+
+    // minimize ||Σ αⱼ yⱼ φ(xⱼ)||² over the majority class subject to Σ αⱼ = n_s (SMO on pairs)
+    let majority: Vec<usize> = (0..n).filter(|&i| y[i] == ym).collect();
+    if n_s < n_m {
+        loop {
+            let up = majority
+                .iter()
+                .filter(|&&i| alpha[i] < 1.0)
+                .min_by(|&&i, &&j| (ym * h[i]).total_cmp(&(ym * h[j])));
+            let down = majority
+                .iter()
+                .filter(|&&j| alpha[j] > 0.0)
+                .max_by(|&&i, &&j| (ym * h[i]).total_cmp(&(ym * h[j])));
+            let (i, j) = match (up, down) {
+                (Some(&i), Some(&j)) => (i, j),
+                _ => break,
+            };
+            let diff = ym * (h[j] - h[i]);
+            if diff <= params.tol {
+                break;
+            }
+            kernel.use_rows(&[i, j], &full_set, &mut |kis| {
+                let (ki, kj) = (kis[0], kis[1]);
+                let q = f64::max(ki[i] + kj[j] - 2.0 * ki[j], problem.regularization());
+                let t = f64::min(diff / q, f64::min(1.0 - alpha[i], alpha[j]));
+                alpha[i] = if t == 1.0 - alpha[i] {
+                    1.0
+                } else {
+                    alpha[i] + t
+                };
+                alpha[j] = if t == alpha[j] { 0.0 } else { alpha[j] - t };
+                for (hk, (kik, kjk)) in h.iter_mut().zip(ki.iter().zip(kj.iter())) {
+                    *hk += ym * t * (kik - kjk);
+                }
+            });
+        }
+    }
+
+    // determine the multiplier μ of the subproblem and the first breakpoint
+    let mut elbow: Vec<usize> = majority
+        .iter()
+        .copied()
+        .filter(|&i| alpha[i] > 0.0 && alpha[i] < 1.0)
+        .collect();
+    let mu = if elbow.is_empty() {
+        let i_mu = majority
+            .iter()
+            .copied()
+            .filter(|&i| alpha[i] == 1.0)
+            .max_by(|&i, &j| (ym * h[i]).total_cmp(&(ym * h[j])))
+            .unwrap();
+        elbow.push(i_mu);
+        ym * h[i_mu]
+    } else {
+        elbow.iter().map(|&i| ym * h[i]).sum::<f64>() / elbow.len() as f64
+    };
+    let i_s = (0..n)
+        .filter(|&i| y[i] == ys)
+        .max_by(|&i, &j| (ys * h[i]).total_cmp(&(ys * h[j])))
+        .unwrap();
+    elbow.push(i_s);
+    let mut lambda = 0.5 * (ys * h[i_s] + mu);
+    let mut alpha0 = ym * (lambda - mu);
+    let mut g: Vec<f64> = h.iter().map(|hi| hi + alpha0).collect();
+
+    let labels = y.clone();
+    let mut breakpoints = Vec::new();
+    let push = |breakpoints: &mut Vec<Breakpoint>,
+                lambda: f64,
+                alpha0: f64,
+                alpha: &[f64],
+                elbow: &[usize],
+                event: Event| {
+        breakpoints.push(Breakpoint {
+            lambda: lambda / shift,
+            b: alpha0 * shift / lambda,
+            elbow: elbow.to_vec(),
+            elbow_a: elbow.iter().map(|&i| labels[i] * alpha[i]).collect(),
+            event,
+        });
+    };
+    let initial_a: Vec<f64> = (0..n).map(|i| y[i] * alpha[i]).collect();
+    push(
+        &mut breakpoints,
+        lambda,
+        alpha0,
+        &alpha,
+        &elbow,
+        Event::Initial,
+    );
+
+    let lambda_min = params.lambda_min * shift;
+    let termination = loop {
+        if lambda <= lambda_min {
+            break Termination::LambdaMin;
+        }
+        if breakpoints.len() >= params.max_steps {
+            break Termination::MaxSteps;
+        }
+        let left = (0..n).any(|i| alpha[i] == 1.0 && !elbow.contains(&i));
+        if !left {
+            break Termination::Separable;
+        }
+
+        // derivatives of (α₀, α_E) wrt λ' from y_E ∘ (K_E (y α) + α₀) = λ' and Σ y_E α_E = const
+        let m = elbow.len();
+        let mut rows = vec![vec![0.0; n]; m];
+        for (idx_i, &i) in elbow.iter().enumerate() {
+            add_row(kernel, i, 1.0, &mut rows[idx_i], &full_set);
+        }
+        let delta = if m == 0 {
+            // only the offset moves (keeping b = α₀ / λ' fixed) until a sample enters the elbow
+            Vector::new(vec![alpha0 / lambda])
+        } else {
+            let mut mat = Matrix::zeros(m + 1, m + 1);
+            for (idx_i, &i) in elbow.iter().enumerate() {
+                mat[[0, idx_i + 1]] = y[i];
+                mat[[idx_i + 1, 0]] = y[i];
+                for (idx_j, &j) in elbow.iter().enumerate() {
+                    mat[[idx_i + 1, idx_j + 1]] = y[i] * y[j] * rows[idx_i][j];
+                }
+            }
+            let mut rhs = Vector::ones(m + 1);
+            rhs[0] = 0.0;
+            match PartialPivLu::decompose(mat).and_then(|lu| lu.solve(rhs)) {
+                Ok(delta) => delta,
+                Err(_) => break Termination::Singular,
+            }
+        };
+        let mut nu = vec![delta[0]; n];
+        for (idx_j, &j) in elbow.iter().enumerate() {
+            for (nuk, kjk) in nu.iter_mut().zip(rows[idx_j].iter()) {
+                *nuk += delta[idx_j + 1] * y[j] * kjk;
+            }
+        }
+
+        // find the next event
+        let threshold = lambda * (1.0 - params.tol);
+        let mut next = (f64::NEG_INFINITY, Event::Final);
+        for (idx_j, &j) in elbow.iter().enumerate() {
+            let dj = delta[idx_j + 1];
+            let (lambda_j, event) = if dj > 0.0 {
+                (lambda - alpha[j] / dj, Event::LeaveRight(j))
+            } else if dj < 0.0 {
+                (lambda + (1.0 - alpha[j]) / dj, Event::LeaveLeft(j))
+            } else {
+                continue;
+            };
+            if lambda_j < threshold && lambda_j > next.0 {
+                next = (lambda_j, event);
+            }
+        }
+        for i in 0..n {
+            if elbow.contains(&i) {
+                continue;
+            }
+            let ynu = y[i] * nu[i];
+            if ynu == 1.0 {
+                continue;
+            }
+            let lambda_i = (lambda * ynu - y[i] * g[i]) / (ynu - 1.0);
+            if lambda_i < threshold && lambda_i > next.0 {
+                next = (lambda_i, Event::Enter(i));
+            }
+        }
+        let (lambda_next, event) = if next.0 > lambda_min {
+            next
+        } else {
+            (lambda_min, Event::Final)
+        };
+        if lambda_next <= 0.0 {
+            break Termination::LambdaMin;
+        }
+
+        // move to the next breakpoint
+        let step = lambda_next - lambda;
+        for (idx_j, &j) in elbow.iter().enumerate() {
+            alpha[j] = f64::clamp(alpha[j] + step * delta[idx_j + 1], 0.0, 1.0);
+        }
+        alpha0 += step * delta[0];
+        for (gi, nui) in g.iter_mut().zip(nu.iter()) {
+            *gi += step * nui;
+        }
+        lambda = lambda_next;
+        match event {
+            Event::Enter(i) => elbow.push(i),
+            Event::LeaveLeft(j) => {
+                alpha[j] = 1.0;
+                elbow.retain(|&k| k != j);
+            }
+            Event::LeaveRight(j) => {
+                alpha[j] = 0.0;
+                elbow.retain(|&k| k != j);
+            }
+            _ => {}
+        }
+        push(&mut breakpoints, lambda, alpha0, &alpha, &elbow, event);
+    };
+
+    HingePath {
+        breakpoints,
+        termination,
+        initial_a,
+        initial_slope: ym * shift,
+        labels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{DualProblem, Params as ProblemParams};
+    use crate::smo;
+    use crate::testing::{classification, rows};
+
+    #[test]
+    fn path_agrees_with_smo() {
+        let (x, y) = classification(25);
+        let mut kernel = gaussian::from_vecs(rows(&x), 2.0);
+        let mut problem = Classification::new(&y, ProblemParams::new());
+        let path = solve(&problem, &mut kernel, &Params::new().with_lambda_min(1e-2));
+        assert_ne!(path.termination, Termination::Singular);
+        let lambdas = path.lambdas();
+        assert!(lambdas.windows(2).all(|w| w[0] >= w[1]));
+        let last = lambdas[lambdas.len() - 1];
+        let mid = 0.5 * (lambdas[1] + lambdas[2]);
+        for &lambda in [2.0 * lambdas[0], lambdas[0], mid, 0.5, 0.3, last].iter() {
+            problem.params.lambda = lambda;
+            let status = path.status(lambda, &mut kernel);
+            let reference = smo::solve(
+                &problem,
+                &mut kernel,
+                &smo::Params::new().with_tol(1e-10),
+                None,
+            );
+            let value = -problem.objective(&status);
+            assert!((value - reference.value).abs() < 1e-6 * (1.0 + reference.value.abs()));
+        }
+    }
+}
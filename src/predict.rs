@@ -1,14 +1,12 @@
-use crate::kernel::KernelFunction;
 use crate::Status;
 
 /// Evaluate the decision function for a particular sample.
-pub fn predict<T>(
-    elem: &T,
-    data: &Vec<T>,
-    status: &Status,
-    lmbda: f64,
-    kernel_function: &KernelFunction<T>,
-) -> f64 {
+///
+/// Works for any sample type with a kernel function, e.g., dense samples or [`SparseRow`](crate::kernel::sparse::SparseRow).
+pub fn predict<T, F>(elem: &T, data: &[T], status: &Status, lmbda: f64, kernel_function: &F) -> f64
+where
+    F: Fn(&T, &T) -> f64 + ?Sized,
+{
     let mut v = 0.0;
     for (&ai, xi) in status.a.iter().zip(data) {
         if ai == 0.0 {
@@ -20,6 +20,11 @@ pub use lssvm::LSSVM;
 mod poisson;
 pub use poisson::Poisson;
 
+mod regularized;
+pub(crate) use regularized::Regularized;
+mod shifted;
+pub(crate) use shifted::Shifted;
+
 /// Combination of primal and dual training problem
 pub trait Problem: PrimalProblem + DualProblem {}
 impl<P> Problem for P where P: PrimalProblem + DualProblem {}
@@ -4,8 +4,34 @@ use super::params::Params;
 use crate::kernel::Kernel;
 use crate::status::Status;
 
+/// Marker for types which can be shared between threads if the `parallel` feature is enabled (implemented by all types otherwise)
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+
+/// Marker for types which can be shared between threads if the `parallel` feature is enabled (implemented by all types otherwise)
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// Marker for types which can be sent to other threads if the `parallel` feature is enabled (implemented by all types otherwise)
+#[cfg(feature = "parallel")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "parallel")]
+impl<T: Send + ?Sized> MaybeSend for T {}
+
+/// Marker for types which can be sent to other threads if the `parallel` feature is enabled (implemented by all types otherwise)
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSend for T {}
+
 /// Base for the definition of a training problem
-pub trait ProblemBase {
+///
+/// With the `parallel` feature, training problems have to be [`Sync`] such that the loops over the variables can be run by several threads.
+pub trait ProblemBase: MaybeSync {
     /// Returns the size of the optimization problem (the number of variables).
     fn size(&self) -> usize;
     /// Returns the sign of the ith variable.
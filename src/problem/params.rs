@@ -1,5 +1,5 @@
 /// Common parameters of a training problem
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Params {
     /// Extent of smoothing of the use max function
     pub smoothing: f64,
@@ -0,0 +1,67 @@
+use super::base::ProblemBase;
+use super::shrinking::ShrinkingBase;
+use super::{DualProblem, Params, PrimalProblem, Problem};
+
+/// Training problem with a different regularization parameter λ
+pub(crate) struct Regularized<'a> {
+    problem: &'a dyn Problem,
+    params: Params,
+}
+
+impl<'a> Regularized<'a> {
+    /// Creates a [`Regularized`] problem with the regularization parameter `lambda`.
+    pub(crate) fn new(problem: &'a dyn Problem, lambda: f64) -> Self {
+        Regularized {
+            problem,
+            params: problem.params().clone().with_lambda(lambda),
+        }
+    }
+}
+
+impl ProblemBase for Regularized<'_> {
+    fn size(&self) -> usize {
+        self.problem.size()
+    }
+    fn sign(&self, i: usize) -> f64 {
+        self.problem.sign(i)
+    }
+    fn params(&self) -> &Params {
+        &self.params
+    }
+}
+
+impl ShrinkingBase for Regularized<'_> {
+    fn lb(&self, i: usize) -> f64 {
+        self.problem.lb(i)
+    }
+    fn ub(&self, i: usize) -> f64 {
+        self.problem.ub(i)
+    }
+}
+
+impl DualProblem for Regularized<'_> {
+    fn is_quad(&self) -> bool {
+        self.problem.is_quad()
+    }
+    fn dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.dloss(i, ai)
+    }
+    fn d_dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.d_dloss(i, ai)
+    }
+    fn d2_dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.d2_dloss(i, ai)
+    }
+}
+
+impl PrimalProblem for Regularized<'_> {
+    fn loss(&self, i: usize, ti: f64) -> f64 {
+        self.problem.loss(i, ti)
+    }
+    fn d_loss(&self, i: usize, ti: f64) -> f64 {
+        self.problem.d_loss(i, ti)
+    }
+    fn d2_loss(&self, i: usize, ti: f64) -> f64 {
+        self.problem.d2_loss(i, ti)
+    }
+}
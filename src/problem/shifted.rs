@@ -0,0 +1,66 @@
+use super::base::ProblemBase;
+use super::shrinking::ShrinkingBase;
+use super::{DualProblem, Params};
+use crate::status::Status;
+
+/// Dual training problem with an additional linear term `Σᵢ pᵢ aᵢ` in the objective function
+pub(crate) struct Shifted<'a> {
+    problem: &'a dyn DualProblem,
+    params: Params,
+    linear: Vec<f64>,
+}
+
+impl<'a> Shifted<'a> {
+    /// Creates a [`Shifted`] problem with the coefficients `linear` of the additional term.
+    pub(crate) fn new(problem: &'a dyn DualProblem, linear: Vec<f64>) -> Self {
+        assert_eq!(
+            linear.len(),
+            problem.size(),
+            "linear term should have one entry per variable"
+        );
+        Shifted {
+            problem,
+            params: problem.params().clone(),
+            linear,
+        }
+    }
+}
+
+impl ProblemBase for Shifted<'_> {
+    fn size(&self) -> usize {
+        self.problem.size()
+    }
+    fn sign(&self, i: usize) -> f64 {
+        self.problem.sign(i)
+    }
+    fn params(&self) -> &Params {
+        &self.params
+    }
+    fn is_optimal(&self, status: &Status, tol: f64) -> bool {
+        self.problem.is_optimal(status, tol)
+    }
+}
+
+impl ShrinkingBase for Shifted<'_> {
+    fn lb(&self, i: usize) -> f64 {
+        self.problem.lb(i)
+    }
+    fn ub(&self, i: usize) -> f64 {
+        self.problem.ub(i)
+    }
+}
+
+impl DualProblem for Shifted<'_> {
+    fn is_quad(&self) -> bool {
+        self.problem.is_quad()
+    }
+    fn dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.dloss(i, ai) + self.linear[i] * ai
+    }
+    fn d_dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.d_dloss(i, ai) + self.linear[i]
+    }
+    fn d2_dloss(&self, i: usize, ai: f64) -> f64 {
+        self.problem.d2_dloss(i, ai)
+    }
+}
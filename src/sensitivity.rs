@@ -7,6 +7,59 @@ use rulinalg::matrix::decomposition::PartialPivLu;
 use rulinalg::matrix::Matrix;
 use rulinalg::vector::Vector;
 
+/// LU factorization of the matrix `K/λ + D` restricted to a set of indices (with a diagonal matrix `D`)
+pub(crate) struct ReducedSystem {
+    lu: PartialPivLu<f64>,
+    size: usize,
+}
+
+impl ReducedSystem {
+    /// Factorizes the matrix given the kernel rows `kis` of the indices (with entries for all variables).
+    pub(crate) fn new(
+        indices: &[usize],
+        kis: &[&[f64]],
+        diag: &[f64],
+        lambda: f64,
+    ) -> Option<Self> {
+        let size = indices.len();
+        let mut mat = Matrix::zeros(size, size);
+        for (idx_i, ki) in kis.iter().enumerate() {
+            for (idx_j, &j) in indices.iter().enumerate() {
+                mat[[idx_i, idx_j]] = ki[j] / lambda;
+            }
+            mat[[idx_i, idx_i]] += diag[idx_i];
+        }
+        let lu = PartialPivLu::decompose(mat).ok()?;
+        Some(ReducedSystem { lu, size })
+    }
+
+    /// Solves the system for the right-hand side `rhs`.
+    pub(crate) fn solve(&self, rhs: &[f64]) -> Option<Vec<f64>> {
+        debug_assert_eq!(rhs.len(), self.size);
+        let sol = self.lu.solve(Vector::from(rhs.to_vec())).ok()?;
+        Some(sol.into_vec())
+    }
+
+    /// Solves the system extended by the constraint `Σᵢ xᵢ = rhs_b` with multiplier `db` (entering as `+ db` in every row).
+    ///
+    /// Returns the solution `x` and the multiplier `db`.
+    pub(crate) fn solve_with_sum(&self, rhs: &[f64], rhs_b: f64) -> Option<(Vec<f64>, f64)> {
+        let mat_inv_rhs = self.solve(rhs)?;
+        let mat_inv_one = self.solve(&vec![1.0; self.size])?;
+        let sum_one: f64 = mat_inv_one.iter().sum();
+        if sum_one == 0.0 {
+            return None;
+        }
+        let db = (mat_inv_rhs.iter().sum::<f64>() - rhs_b) / sum_one;
+        let sol = mat_inv_rhs
+            .iter()
+            .zip(mat_inv_one.iter())
+            .map(|(xi, oi)| xi - db * oi)
+            .collect();
+        Some((sol, db))
+    }
+}
+
 /// Solves the transposed linearized system
 pub fn solve_transposed_linearization(
     problem: &dyn PrimalProblem,
@@ -34,30 +87,33 @@ pub fn solve_transposed_linearization(
         }
     }
 
-    let rhs0 = Vector::from(rhs.a.clone());
+    let rhs0: Vector<f64> = status_ext
+        .active
+        .positives()
+        .iter()
+        .map(|&i| rhs.a[i])
+        .collect();
+    let diag: Vec<f64> = status_ext
+        .active
+        .positives()
+        .iter()
+        .map(|&i| 1.0 / status_ext.h[i])
+        .collect();
     let mut sol = Direction::new(problem.size());
     let full_set: Vec<_> = (0..problem.size()).collect();
     kernel.use_rows(status_ext.active.positives(), &full_set, &mut |kis| {
-        let mut mat = Matrix::zeros(n_active, n_active);
-        for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
-            for (idx_j, &j) in status_ext.active.positives().iter().enumerate() {
-                mat[[idx_i, idx_j]] = kis[idx_i][j] / problem.lambda();
-                if idx_i == idx_j {
-                    mat[[idx_i, idx_j]] += 1.0 / status_ext.h[i];
-                }
-            }
-        }
-        let mat_fact = PartialPivLu::decompose(mat).unwrap();
-
-        let mat_inv_rhs = mat_fact.solve(rhs0.clone()).unwrap();
-        let mat_inv_one = mat_fact.solve(Vector::ones(n_active)).unwrap();
+        let system =
+            ReducedSystem::new(status_ext.active.positives(), &kis, &diag, problem.lambda())
+                .unwrap();
 
         let vanishing: Vec<_> = (0..problem.size())
             .filter(|&i| status_ext.h[i] == 0.0)
             .collect();
-        if problem.has_max_asum() {
+        let x: Vec<f64> = if problem.has_max_asum() {
             // solve system with two additional constraints
-            let mat_inv_signs = mat_fact.solve(signs.clone()).unwrap();
+            let mat_inv_rhs = Vector::new(system.solve(rhs0.data()).unwrap());
+            let mat_inv_one = Vector::new(system.solve(&vec![1.0; n_active]).unwrap());
+            let mat_inv_signs = Vector::new(system.solve(signs.data()).unwrap());
             // create and solve 2x2 system
             let q00 = mat_inv_one.sum();
             let q01 = mat_inv_signs.sum();
@@ -70,23 +126,88 @@ pub fn solve_transposed_linearization(
             sol.b = db;
             let dc = (q00 * p1 - q01 * p0) / det;
             sol.c = dc;
-            for &j in vanishing.iter() {
-                sol.a[j] = rhs0[j] - db - dc * problem.sign(j);
-            }
+            (0..n_active)
+                .map(|k| mat_inv_rhs[k] - db * mat_inv_one[k] - dc * mat_inv_signs[k])
+                .collect()
         } else {
-            // solve system with one additional constraints
-            let db = (mat_inv_rhs.sum() - rhs.b) / mat_inv_one.sum();
+            // solve system with one additional constraint
+            let (x, db) = system.solve_with_sum(rhs0.data(), rhs.b).unwrap();
             sol.b = db;
-            for &j in vanishing.iter() {
-                sol.a[j] = rhs.a[j] - db;
-            }
+            x
         };
+        for &j in vanishing.iter() {
+            sol.a[j] = rhs.a[j] - sol.b - sol.c * problem.sign(j);
+        }
         for (idx_i, &i) in status_ext.active.positives().iter().enumerate() {
-            sol.a[i] = mat_inv_rhs[idx_i] / status_ext.h[i];
+            sol.a[i] = x[idx_i] / status_ext.h[i];
             for &j in vanishing.iter() {
-                sol.a[j] -= kis[idx_i][j] / problem.lambda() * mat_inv_rhs[idx_i];
+                sol.a[j] -= kis[idx_i][j] / problem.lambda() * x[idx_i];
             }
         }
     });
     return sol;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::newton;
+    use crate::problem::base::ProblemBase;
+    use crate::problem::{Classification, Params};
+    use crate::testing::{classification, rows};
+
+    /// Checks `Aᵀ sol = rhs` for the linearization `A` of the optimality conditions,
+    /// i.e., `daᵢ + hᵢ (Σⱼ kᵢⱼ daⱼ / λ + db + sᵢ dc)` for every variable and the constraints `Σ daᵢ` and `Σ sᵢ daᵢ`.
+    fn check_transposed(max_asum: f64) {
+        let (x, y) = classification(30);
+        let mut kernel = gaussian::from_vecs(rows(&x), 1.0);
+        let params = Params::new()
+            .with_lambda(0.1)
+            .with_smoothing(0.5)
+            .with_max_asum(max_asum);
+        let problem = Classification::new(&y, params);
+        let mut status_ext = newton::solve(&problem, &mut kernel, &newton::Params::new(), None);
+        let n = problem.size();
+        let h = status_ext.h.clone();
+        assert!(h.contains(&0.0) && h.iter().any(|&hi| hi > 0.0));
+
+        let mut rhs = Direction::new(n);
+        for (i, rhs_i) in rhs.a.iter_mut().enumerate() {
+            *rhs_i = (i as f64 * 2.3).sin();
+        }
+        rhs.b = 0.7;
+        rhs.c = if problem.has_max_asum() { -0.4 } else { 0.0 };
+        let sol = solve_transposed_linearization(&problem, &mut kernel, &mut status_ext, &rhs);
+
+        let full_set: Vec<usize> = (0..n).collect();
+        let mut ki = vec![0.0; n];
+        let mut kernel_sol = vec![0.0; n];
+        for (i, kernel_sol_i) in kernel_sol.iter_mut().enumerate() {
+            kernel.compute_row(i, &mut ki, &full_set);
+            *kernel_sol_i = (0..n).map(|j| ki[j] * h[j] * sol.a[j]).sum::<f64>() / problem.lambda();
+        }
+        // rows of Aᵀ belonging to the coefficients, the offset and the shift
+        for (j, kernel_sol_j) in kernel_sol.iter().enumerate() {
+            let sj = problem.sign(j);
+            let value = sol.a[j] + kernel_sol_j + sol.b + sj * sol.c;
+            assert!((value - rhs.a[j]).abs() < 1e-8);
+        }
+        let value_b: f64 = (0..n).map(|i| h[i] * sol.a[i]).sum();
+        assert!((value_b - rhs.b).abs() < 1e-8);
+        if problem.has_max_asum() {
+            let value_c: f64 = (0..n).map(|i| h[i] * problem.sign(i) * sol.a[i]).sum();
+            assert!((value_c - rhs.c).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn transposed_linearization() {
+        check_transposed(f64::INFINITY);
+    }
+
+    #[test]
+    fn transposed_linearization_with_max_asum() {
+        check_transposed(2.0);
+    }
+}
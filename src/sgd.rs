@@ -0,0 +1,13 @@
+//! Stochastic gradient descent for primal training problems
+//!
+//! Both methods minimize `λ/2 ‖w‖² + Σᵢ Lᵢ(w·φ(xᵢ) + b)` using the derivative [`PrimalProblem::d_loss`](crate::problem::PrimalProblem::d_loss)
+//! at a single random sample per step with the Pegasos step size `n / (λ t)`.
+//! As in [`dcd`](crate::dcd), the offset is regularized by a constant feature (see [`Params::bias`]).
+//! Samples arriving as a stream can be used one at a time with [`Pegasos::step`] and [`BudgetedSgd::step`].
+
+mod params;
+pub use params::{Maintenance, Params};
+mod budgeted;
+pub use budgeted::{budgeted, BudgetedModel, BudgetedSgd};
+mod pegasos;
+pub use pegasos::{pegasos, Pegasos, Update};
@@ -0,0 +1,293 @@
+use crate::kernel::gaussian;
+use crate::problem::PrimalProblem;
+use crate::time::{now, until_now};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{Maintenance, Params};
+
+/// Number of golden section steps for finding the merged support vector
+const MERGE_SEARCH_STEPS: usize = 20;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Model with a bounded number of support vectors for the Gaussian kernel
+///
+/// The decision function is `f(x) = Σⱼ coefⱼ k(supportⱼ, x) + b`.
+/// Merged support vectors are in general no training samples.
+pub struct BudgetedModel {
+    /// Support vectors
+    pub support: Vec<Vec<f64>>,
+    /// Coefficients of the support vectors
+    pub coef: Vec<f64>,
+    /// Offset of the decision function
+    pub b: f64,
+    /// Parameter of the Gaussian kernel
+    pub gamma: f64,
+    /// Number of conducted steps
+    pub steps: usize,
+    /// Elapsed time (in seconds)
+    pub time: f64,
+}
+
+impl BudgetedModel {
+    /// Evaluates the decision function for a sample.
+    pub fn predict(&self, x: &[f64]) -> f64 {
+        self.support
+            .iter()
+            .zip(self.coef.iter())
+            .map(|(z, cj)| cj * gaussian::kernel(z, x, self.gamma))
+            .sum::<f64>()
+            + self.b
+    }
+
+    /// Returns the position of the support vector with the smallest absolute coefficient.
+    fn smallest(&self) -> usize {
+        (0..self.coef.len())
+            .min_by(|&j, &k| self.coef[j].abs().total_cmp(&self.coef[k].abs()))
+            .unwrap()
+    }
+
+    /// Merges the `j`th support vector into the partner leading to the smallest weight degradation (among the ones with coefficients of the same sign).
+    ///
+    /// Returns the position of the partner (replaced by the merged vector) or `None` if no partner exists.
+    /// The `j`th support vector itself is not removed.
+    fn merge(&mut self, j: usize) -> Option<usize> {
+        let cj = self.coef[j];
+        // (degradation, partner, h, coefficient)
+        let mut best: Option<(f64, usize, f64, f64)> = None;
+        for k in 0..self.coef.len() {
+            let ck = self.coef[k];
+            if k == j || cj * ck <= 0.0 {
+                continue;
+            }
+            // merged vector z = h x_j + (1 - h) x_k with k(x_j, z) = kjk^((1-h)²) and k(x_k, z) = kjk^(h²)
+            let kjk = gaussian::kernel(&self.support[j], &self.support[k], self.gamma);
+            let coef_z = |h: f64| cj * kjk.powf((1.0 - h) * (1.0 - h)) + ck * kjk.powf(h * h);
+            let (mut lo, mut hi) = (0.0, 1.0);
+            let ratio = 0.5 * (5f64.sqrt() - 1.0);
+            for _search_step in 0..MERGE_SEARCH_STEPS {
+                let h1 = hi - ratio * (hi - lo);
+                let h2 = lo + ratio * (hi - lo);
+                if coef_z(h1).abs() >= coef_z(h2).abs() {
+                    hi = h2;
+                } else {
+                    lo = h1;
+                }
+            }
+            let h = 0.5 * (lo + hi);
+            let cz = coef_z(h);
+            let degradation = cj * cj + ck * ck + 2.0 * cj * ck * kjk - cz * cz;
+            if best.is_none_or(|(d, ..)| degradation < d) {
+                best = Some((degradation, k, h, cz));
+            }
+        }
+        let (_degradation, k, h, cz) = best?;
+        let z = self.support[j]
+            .iter()
+            .zip(self.support[k].iter())
+            .map(|(xj, xk)| h * xj + (1.0 - h) * xk)
+            .collect();
+        self.support[k] = z;
+        self.coef[k] = cz;
+        Some(k)
+    }
+}
+
+/// Budgeted kernel SGD with the Gaussian kernel, which is trained one sample at a time
+///
+/// The objective function `λ/2 ‖w‖² + Σᵢ Lᵢ(w·φ(xᵢ) + b)` with `n` samples uses the Pegasos step size `n / (λ t)`.
+/// Whenever the number of support vectors exceeds [`Params::budget`], it is reduced according to [`Params::maintenance`].
+#[derive(Clone, Debug)]
+pub struct BudgetedSgd {
+    model: BudgetedModel,
+    /// Samples belonging to the support vectors (`None` for merged or streamed ones)
+    sources: Vec<Option<usize>>,
+    lambda: f64,
+    n: usize,
+    bias: f64,
+    budget: usize,
+    maintenance: Maintenance,
+    rng: StdRng,
+}
+
+impl BudgetedSgd {
+    /// Creates an empty model for the Gaussian kernel with parameter `gamma`
+    /// and the regularization parameter `lambda` and the number of samples `n` of the objective function.
+    pub fn new(gamma: f64, lambda: f64, n: usize, params: &Params) -> Self {
+        assert!(n > 0, "number of samples should be positive");
+        BudgetedSgd {
+            model: BudgetedModel {
+                support: Vec::new(),
+                coef: Vec::new(),
+                b: 0.0,
+                gamma,
+                steps: 0,
+                time: 0.0,
+            },
+            sources: Vec::new(),
+            lambda,
+            n,
+            bias: params.bias,
+            budget: params.budget,
+            maintenance: params.maintenance,
+            rng: StdRng::seed_from_u64(params.seed),
+        }
+    }
+
+    /// Returns the current model.
+    pub fn model(&self) -> &BudgetedModel {
+        &self.model
+    }
+
+    /// Returns the current model (consuming the state of the method).
+    pub fn into_model(self) -> BudgetedModel {
+        self.model
+    }
+
+    /// Conducts a step using the sample `x` and the derivative `d_loss` of its loss function
+    /// (e.g., `|t| problem.d_label_loss(0, t, y)` for a label `y`).
+    ///
+    /// Every sample with a nonvanishing loss derivative becomes a new support vector.
+    pub fn step(&mut self, x: &[f64], d_loss: impl Fn(f64) -> f64) {
+        self.step_with_source(x, None, d_loss);
+    }
+
+    /// Same as [`BudgetedSgd::step`], where repeated samples with the same `source` share their support vector.
+    fn step_with_source(&mut self, x: &[f64], source: Option<usize>, d_loss: impl Fn(f64) -> f64) {
+        let model = &mut self.model;
+        model.steps += 1;
+        let step = model.steps;
+        let gi = d_loss(model.predict(x));
+
+        // shrink by (1 - 1/t) and add the scaled loss derivative with step size n / (λ t)
+        let factor = 1.0 - 1.0 / step as f64;
+        model.coef.iter_mut().for_each(|cj| *cj *= factor);
+        model.b *= factor;
+        if gi == 0.0 {
+            return;
+        }
+        let dc = -(self.n as f64) / (self.lambda * step as f64) * gi;
+        model.b += dc * self.bias * self.bias;
+        match source.and_then(|_| self.sources.iter().position(|&s| s == source)) {
+            Some(j) => model.coef[j] += dc,
+            None => {
+                model.support.push(x.to_vec());
+                model.coef.push(dc);
+                self.sources.push(source);
+            }
+        }
+
+        // maintain budget
+        if model.coef.len() > self.budget {
+            let j = match self.maintenance {
+                Maintenance::RemoveSmallest | Maintenance::Merge => model.smallest(),
+                Maintenance::RemoveRandom => self.rng.gen_range(0..model.coef.len()),
+            };
+            if self.maintenance == Maintenance::Merge {
+                if let Some(k) = model.merge(j) {
+                    self.sources[k] = None;
+                }
+            }
+            model.support.swap_remove(j);
+            model.coef.swap_remove(j);
+            self.sources.swap_remove(j);
+        }
+    }
+}
+
+/// Uses budgeted stochastic gradient descent with the Gaussian kernel on `data` to approximately solve the given training problem (see [`BudgetedSgd`]).
+///
+/// The `i`th variable belongs to the sample `data[i % data.len()]`.
+pub fn budgeted(
+    problem: &dyn PrimalProblem,
+    data: &[&[f64]],
+    gamma: f64,
+    params: &Params,
+) -> BudgetedModel {
+    assert!(
+        !problem.has_max_asum(),
+        "bound on the 1-norm is not supported"
+    );
+    let start = now();
+    let n = problem.size();
+    let m = data.len();
+    assert!(n > 0 && m > 0, "problem and data should not be empty");
+    let mut sgd = BudgetedSgd::new(gamma, problem.lambda(), n, params);
+
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    while sgd.model.steps < params.max_steps {
+        if params.time_limit > 0.0 && until_now(start) >= params.time_limit {
+            break;
+        }
+        let i = rng.gen_range(0..n);
+        sgd.step_with_source(data[i % m], Some(i % m), |t| problem.d_loss(i, t));
+    }
+    let mut model = sgd.into_model();
+    model.time = until_now(start);
+    model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::base::ProblemBase;
+    use crate::problem::{Classification, Params as ProblemParams, PrimalLabelProblem};
+    use crate::testing::{classification, rows};
+
+    fn accuracy(model: &BudgetedModel, x: &[Vec<f64>], y: &[f64]) -> f64 {
+        let correct = x
+            .iter()
+            .zip(y.iter())
+            .filter(|(xi, &yi)| model.predict(xi) * yi > 0.0)
+            .count();
+        correct as f64 / x.len() as f64
+    }
+
+    #[test]
+    fn respects_budget() {
+        let (x, y) = classification(60);
+        let dense = rows(&x);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.01));
+        for maintenance in [
+            Maintenance::RemoveSmallest,
+            Maintenance::RemoveRandom,
+            Maintenance::Merge,
+        ] {
+            let params = Params::new()
+                .with_max_steps(20000)
+                .with_budget(30)
+                .with_maintenance(maintenance);
+            let model = budgeted(&problem, &dense, 2.0, &params);
+            assert_eq!(model.steps, params.max_steps);
+            assert!(model.coef.len() <= params.budget);
+            assert_eq!(model.support.len(), model.coef.len());
+            assert!(accuracy(&model, &x, &y) > 0.9);
+        }
+    }
+
+    #[test]
+    fn streaming_steps() {
+        let (x, y) = classification(60);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.01));
+        let params = Params::new().with_budget(20);
+        let mut sgd = BudgetedSgd::new(2.0, problem.lambda(), x.len(), &params);
+        for _epoch in 0..50 {
+            for (i, xi) in x.iter().enumerate() {
+                sgd.step(xi, |t| problem.d_label_loss(i, t, y[i]));
+                assert!(sgd.model().coef.len() <= params.budget);
+            }
+        }
+        let model = sgd.into_model();
+        assert_eq!(model.steps, 50 * x.len());
+        assert!(accuracy(&model, &x, &y) > 0.9);
+    }
+
+    #[test]
+    #[should_panic(expected = "problem and data should not be empty")]
+    fn empty_data() {
+        let problem = Classification::new(&[1.0], ProblemParams::new());
+        let data: Vec<&[f64]> = Vec::new();
+        budgeted(&problem, &data, 1.0, &Params::new());
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// Strategies to keep the number of support vectors within the budget
+pub enum Maintenance {
+    /// Removes the support vector with the smallest absolute coefficient.
+    RemoveSmallest,
+    /// Removes a random support vector.
+    RemoveRandom,
+    /// Merges the support vector with the smallest absolute coefficient with the one leading to the smallest weight degradation.
+    Merge,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Parameters of the stochastic gradient descent methods
+pub struct Params {
+    /// Number of steps (each using a single random sample)
+    pub max_steps: usize,
+    /// Time limit (in seconds)
+    pub time_limit: f64,
+    /// Value of the constant feature appended to each sample (`0` for no offset)
+    pub bias: f64,
+    /// Maximum number of support vectors (only used by the budgeted kernel SGD)
+    pub budget: usize,
+    /// Strategy to keep the number of support vectors within the budget
+    pub maintenance: Maintenance,
+    /// Random seed for sampling
+    pub seed: u64,
+}
+
+impl Params {
+    /// Creates a new [`Params`] struct with default parameter values.
+    pub fn new() -> Self {
+        Params {
+            max_steps: 100000,
+            time_limit: f64::INFINITY,
+            bias: 1.0,
+            budget: 500,
+            maintenance: Maintenance::Merge,
+            seed: 0,
+        }
+    }
+
+    /// Updates the number of steps.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Updates the time limit.
+    pub fn with_time_limit(mut self, time_limit: f64) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Updates the value of the constant feature.
+    pub fn with_bias(mut self, bias: f64) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Updates the maximum number of support vectors.
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        assert!(budget > 0, "budget should be positive");
+        self.budget = budget;
+        self
+    }
+
+    /// Updates the budget maintenance strategy.
+    pub fn with_maintenance(mut self, maintenance: Maintenance) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Updates the random seed.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,252 @@
+use crate::dcd::Row;
+use crate::problem::PrimalProblem;
+use crate::status::{Status, StatusCode};
+use crate::time::{now, until_now};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::Params;
+
+/// Smallest scale factor of the weight vector before it is applied to the stored values
+const MIN_SCALE: f64 = 1e-9;
+
+/// Change of the coefficients `a` (with `w = Σ aⱼ xⱼ / λ`) in a step of [`Pegasos`]
+#[derive(Clone, Copy, Debug)]
+pub struct Update {
+    /// Factor applied to all previous coefficients
+    pub factor: f64,
+    /// Change of the coefficient of the current sample (added after applying the factor)
+    pub da: f64,
+}
+
+/// Linear model trained by the Pegasos method one sample at a time
+///
+/// The model minimizes `λ/2 ‖w‖² + Σᵢ Lᵢ(w·xᵢ + b)` for `n` samples, which may also arrive as a stream (see [`Pegasos::step`]).
+/// The weight vector is stored as `w = s v` with a scalar `s` such that the shrinkage of each step costs `O(1)`.
+/// After each step, it is projected onto the ball `‖w‖² ≤ 2 Σᵢ Lᵢ(0) / λ` containing the solution,
+/// which prevents the large initial steps from diverging for losses with unbounded derivatives.
+#[derive(Clone, Debug)]
+pub struct Pegasos {
+    /// Scaled weights (the last entry belongs to the constant feature)
+    v: Vec<f64>,
+    scale: f64,
+    /// Squared norm of `v`
+    norm_sqr: f64,
+    /// Squared radius of the ball containing the solution
+    radius_sqr: f64,
+    lambda: f64,
+    n: usize,
+    bias: f64,
+    steps: usize,
+}
+
+impl Pegasos {
+    /// Creates a model with vanishing weights for samples of dimension `dim`.
+    ///
+    /// * `lambda`, `n`: regularization parameter and number of samples of the objective function
+    /// * `loss_at_zero`: sum `Σᵢ Lᵢ(0)` of the losses at zero for the projection (`f64::INFINITY` for no projection)
+    /// * `params`: parameters (only [`Params::bias`] is used)
+    pub fn new(dim: usize, lambda: f64, n: usize, loss_at_zero: f64, params: &Params) -> Self {
+        assert!(n > 0, "number of samples should be positive");
+        Pegasos {
+            v: vec![0.0; dim + 1],
+            scale: 1.0,
+            norm_sqr: 0.0,
+            radius_sqr: 2.0 * loss_at_zero / lambda,
+            lambda,
+            n,
+            bias: params.bias,
+            steps: 0,
+        }
+    }
+
+    /// Returns the number of conducted steps.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the weight vector `w` (without the entry of the constant feature).
+    pub fn weights(&self) -> Vec<f64> {
+        let dim = self.v.len() - 1;
+        self.v[..dim].iter().map(|vk| self.scale * vk).collect()
+    }
+
+    /// Returns the offset `b` of the decision function.
+    pub fn offset(&self) -> f64 {
+        let dim = self.v.len() - 1;
+        self.bias * self.scale * self.v[dim]
+    }
+
+    /// Evaluates the decision function `w·x + b` for a sample.
+    pub fn decision<R: Row>(&self, x: &R) -> f64 {
+        let dim = self.v.len() - 1;
+        self.scale * (x.dot_weights(&self.v[..dim]) + self.bias * self.v[dim])
+    }
+
+    /// Conducts a step using the sample `x` and the derivative `d_loss` of its loss function
+    /// (e.g., `|t| problem.d_label_loss(0, t, y)` for a label `y`).
+    pub fn step<R: Row>(&mut self, x: &R, d_loss: impl Fn(f64) -> f64) -> Update {
+        let dim = self.v.len() - 1;
+        assert!(
+            x.dim() <= dim,
+            "sample should fit the dimension of the model"
+        );
+        self.steps += 1;
+        let mut xv = x.dot_weights(&self.v[..dim]) + self.bias * self.v[dim];
+        let gi = d_loss(self.scale * xv);
+
+        // shrink by (1 - 1/t) and add the scaled loss derivative with step size n / (λ t)
+        let shrink = 1.0 - 1.0 / self.steps as f64;
+        self.scale *= shrink;
+        if self.scale < MIN_SCALE {
+            for vk in self.v.iter_mut() {
+                *vk *= self.scale;
+            }
+            self.norm_sqr *= self.scale * self.scale;
+            xv *= self.scale;
+            self.scale = 1.0;
+        }
+        if gi == 0.0 {
+            return Update {
+                factor: shrink,
+                da: 0.0,
+            };
+        }
+        let eta = self.n as f64 / (self.lambda * self.steps as f64);
+        let da = -eta * gi * self.lambda;
+        let factor = da / (self.lambda * self.scale);
+        x.add_to_weights(&mut self.v[..dim], factor);
+        self.v[dim] += factor * self.bias;
+        self.norm_sqr +=
+            2.0 * factor * xv + factor * factor * (x.norm_sqr() + self.bias * self.bias);
+        self.norm_sqr = f64::max(self.norm_sqr, 0.0);
+
+        // project onto the ball
+        let w_norm_sqr = self.scale * self.scale * self.norm_sqr;
+        let projection = if w_norm_sqr > self.radius_sqr {
+            (self.radius_sqr / w_norm_sqr).sqrt()
+        } else {
+            1.0
+        };
+        self.scale *= projection;
+        Update {
+            factor: projection * shrink,
+            da: projection * da,
+        }
+    }
+}
+
+/// Uses the Pegasos method (with the linear kernel on `data`) to approximately solve the given training problem (see [`Pegasos`]).
+///
+/// The result contains the coefficients `a` with `w = Σ aᵢ xᵢ / λ`,
+/// where the `i`th variable belongs to the sample `data[i % data.len()]`,
+/// such that it can be used like the results of the other solvers (with the linear kernel).
+pub fn pegasos<R: Row>(problem: &dyn PrimalProblem, data: &[R], params: &Params) -> Status {
+    assert!(
+        !problem.has_max_asum(),
+        "bound on the 1-norm is not supported"
+    );
+    let start = now();
+    let n = problem.size();
+    let m = data.len();
+    assert!(n > 0 && m > 0, "problem and data should not be empty");
+    let lambda = problem.lambda();
+    let bias = params.bias;
+    let mut status = Status::new(n);
+
+    let dim = data.iter().map(|x| x.dim()).max().unwrap_or(0);
+    let loss_at_zero = (0..n).map(|i| problem.loss(i, 0.0)).sum::<f64>();
+    let mut model = Pegasos::new(dim, lambda, n, loss_at_zero, params);
+    // a = scale * a_scaled
+    let mut a_scaled = vec![0.0; n];
+    let mut scale = 1.0;
+
+    let mut rng = StdRng::seed_from_u64(params.seed);
+    status.opt_status.code = StatusCode::MaxSteps;
+    while model.steps() < params.max_steps {
+        if params.time_limit > 0.0 && until_now(start) >= params.time_limit {
+            status.opt_status.code = StatusCode::TimeLimit;
+            break;
+        }
+        let i = rng.gen_range(0..n);
+        let update = model.step(&data[i % m], |t| problem.d_loss(i, t));
+        scale *= update.factor;
+        if scale < MIN_SCALE {
+            for ak in a_scaled.iter_mut() {
+                *ak *= scale;
+            }
+            scale = 1.0;
+        }
+        a_scaled[i] += update.da / scale;
+    }
+
+    // compute the status from the coefficients
+    for (ak, &ak_scaled) in status.a.iter_mut().zip(a_scaled.iter()) {
+        *ak = scale * ak_scaled;
+    }
+    let sum: f64 = status.a.iter().sum();
+    let w = model.weights();
+    for k in 0..n {
+        status.ka[k] = data[k % m].dot_weights(&w);
+    }
+    status.b = bias * bias * sum / lambda;
+    for k in 0..n {
+        status.g[k] = problem.d_loss(k, status.ka[k] + status.b);
+    }
+    status.value = problem.objective(&status) + 0.5 * status.b * sum;
+    status.opt_status.steps = model.steps();
+    status.opt_status.time = until_now(start);
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::problem::base::ProblemBase;
+    use crate::problem::{Classification, Params as ProblemParams, PrimalLabelProblem, LSSVM};
+    use crate::testing::{classification, rows};
+
+    #[test]
+    fn approaches_dcd_objective() {
+        let (x, y) = classification(40);
+        let dense = rows(&x);
+        let problem = LSSVM::new(&y, ProblemParams::new().with_lambda(1.0));
+        let dcd_params = crate::dcd::Params::new().with_tol(1e-8);
+        let optimum = crate::dcd::solve(&problem, &dense, &dcd_params, None).value;
+        let status = pegasos(&problem, &dense, &Params::new().with_max_steps(200000));
+        assert!(matches!(status.opt_status.code, StatusCode::MaxSteps));
+        assert!(status.value >= optimum - 1e-6);
+        assert!(status.value <= optimum + 0.01 * optimum.abs());
+    }
+
+    #[test]
+    fn streaming_steps_match_batch() {
+        let (x, y) = classification(40);
+        let dense = rows(&x);
+        let problem = Classification::new(&y, ProblemParams::new().with_lambda(0.1));
+        let params = Params::new().with_max_steps(1000).with_seed(1);
+        let status = pegasos(&problem, &dense, &params);
+
+        let n = dense.len();
+        let loss_at_zero = (0..n).map(|i| problem.loss(i, 0.0)).sum::<f64>();
+        let mut model = Pegasos::new(2, problem.lambda(), n, loss_at_zero, &params);
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        for _ in 0..params.max_steps {
+            let i = rng.gen_range(0..n);
+            model.step(&dense[i], |t| problem.d_label_loss(i, t, y[i]));
+        }
+        assert_eq!(model.steps(), status.opt_status.steps);
+        assert!((model.offset() - status.b).abs() < 1e-10);
+        for (k, xk) in dense.iter().enumerate() {
+            assert!((model.decision(xk) - status.ka[k] - status.b).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "problem and data should not be empty")]
+    fn empty_problem() {
+        let problem = Classification::new(&[], ProblemParams::new());
+        let data: Vec<&[f64]> = Vec::new();
+        pegasos(&problem, &data, &Params::new());
+    }
+}
@@ -1,5 +1,7 @@
 //! Sequential Minimal Optimization
 
+mod chunks;
+mod decomposition;
 mod params;
 mod subproblem;
 mod update;
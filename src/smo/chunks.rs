@@ -0,0 +1,158 @@
+use crate::kernel::ROW_CHUNK_SIZE;
+use crate::problem::base::MaybeSync;
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Applies `fun` to consecutive ranges of positions `0..len` (concurrently if the `parallel` feature is enabled) and returns the results in order.
+pub fn map_chunks<R, F>(len: usize, fun: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>) -> R + MaybeSync,
+{
+    let ranges: Vec<Range<usize>> = (0..len)
+        .step_by(ROW_CHUNK_SIZE)
+        .map(|start| start..usize::min(start + ROW_CHUNK_SIZE, len))
+        .collect();
+    #[cfg(feature = "parallel")]
+    let ranges = ranges.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let ranges = ranges.into_iter();
+    ranges.map(&fun).collect()
+}
+
+/// Like [`map_chunks`] for the positions of `active_set`, where `fun` additionally gets mutable access to the entries of `values` belonging to the range.
+///
+/// The part of `values` handed over starts at the index given as third argument.
+/// The active set has to be sorted increasingly (which is checked) such that the parts are disjoint.
+pub fn map_chunks_mut<R, F>(values: &mut [f64], active_set: &[usize], fun: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>, &mut [f64], usize) -> R + MaybeSync,
+{
+    assert!(
+        active_set.windows(2).all(|w| w[0] < w[1]),
+        "active set should be sorted increasingly"
+    );
+    let mut parts = Vec::with_capacity(active_set.len() / ROW_CHUNK_SIZE + 1);
+    let mut rest = values;
+    let mut offset = 0;
+    for start in (0..active_set.len()).step_by(ROW_CHUNK_SIZE) {
+        let end = usize::min(start + ROW_CHUNK_SIZE, active_set.len());
+        let next = if end < active_set.len() {
+            active_set[end]
+        } else {
+            offset + rest.len()
+        };
+        let (part, tail) = std::mem::take(&mut rest).split_at_mut(next - offset);
+        parts.push((start..end, part, offset));
+        rest = tail;
+        offset = next;
+    }
+    #[cfg(feature = "parallel")]
+    let parts = parts.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let parts = parts.into_iter();
+    parts
+        .map(|(range, part, offset)| fun(range, part, offset))
+        .collect()
+}
+
+/// Combines two candidates `(value, position)` by keeping the larger value and the smaller position in case of ties,
+/// such that reductions do not depend on the order of evaluation.
+pub fn max_first(x: (f64, usize), y: (f64, usize)) -> (f64, usize) {
+    if y.0 > x.0 || (y.0 == x.0 && y.1 < x.1) {
+        y
+    } else {
+        x
+    }
+}
+
+/// Same as [`max_first`] for the smaller value.
+pub fn min_first(x: (f64, usize), y: (f64, usize)) -> (f64, usize) {
+    if y.0 < x.0 || (y.0 == x.0 && y.1 < x.1) {
+        y
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns values with many ties.
+    fn values(n: usize) -> Vec<f64> {
+        (0..n).map(|i| ((7 * i) % 13) as f64).collect()
+    }
+
+    #[test]
+    fn reductions_agree_with_sequential() {
+        let n = 3 * ROW_CHUNK_SIZE + 5;
+        let v = values(n);
+        let mut max_seq = (f64::NEG_INFINITY, 0);
+        let mut min_seq = (f64::INFINITY, 0);
+        for (i, &vi) in v.iter().enumerate() {
+            if vi > max_seq.0 {
+                max_seq = (vi, i);
+            }
+            if vi < min_seq.0 {
+                min_seq = (vi, i);
+            }
+        }
+
+        let chunks = map_chunks(n, |range| {
+            let max = range
+                .clone()
+                .map(|i| (v[i], i))
+                .fold((f64::NEG_INFINITY, 0), max_first);
+            let min = range.map(|i| (v[i], i)).fold((f64::INFINITY, 0), min_first);
+            (max, min)
+        });
+        assert_eq!(chunks.len(), 4);
+        let (max, min) = chunks.into_iter().rev().fold(
+            ((f64::NEG_INFINITY, 0), (f64::INFINITY, 0)),
+            |(max, min), (chunk_max, chunk_min)| {
+                (max_first(max, chunk_max), min_first(min, chunk_min))
+            },
+        );
+        assert_eq!(max, max_seq);
+        assert_eq!(min, min_seq);
+    }
+
+    #[test]
+    fn mutable_chunks_agree_with_sequential() {
+        let n = 6 * ROW_CHUNK_SIZE + 11;
+        let active_set: Vec<usize> = (0..n).filter(|i| i % 3 != 1).collect();
+        let mut v = values(n);
+        let chunks = map_chunks_mut(&mut v, &active_set, |range, part, offset| {
+            let mut max = (f64::NEG_INFINITY, 0);
+            for idx in range {
+                let i = active_set[idx];
+                part[i - offset] += 1.0;
+                max = max_first(max, (part[i - offset], idx));
+            }
+            max
+        });
+        let max = chunks.into_iter().fold((f64::NEG_INFINITY, 0), max_first);
+
+        let mut expected = values(n);
+        let mut max_seq = (f64::NEG_INFINITY, 0);
+        for (idx, &i) in active_set.iter().enumerate() {
+            expected[i] += 1.0;
+            if expected[i] > max_seq.0 {
+                max_seq = (expected[i], idx);
+            }
+        }
+        assert_eq!(v, expected);
+        assert_eq!(max, max_seq);
+    }
+
+    #[test]
+    #[should_panic(expected = "active set should be sorted increasingly")]
+    fn unsorted_active_set() {
+        let mut v = values(10);
+        map_chunks_mut(&mut v, &[3, 1, 5], |_range, _part, _offset| ());
+    }
+}
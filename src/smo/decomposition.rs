@@ -0,0 +1,192 @@
+use super::subproblem::{compute_step, Subproblem};
+use crate::kernel::Kernel;
+use crate::problem::DualProblem;
+use crate::status::Status;
+
+/// Maximum number of inner SMO steps per variable of the working set
+const INNER_STEPS_PER_VARIABLE: usize = 10;
+
+/// Selects up to `q` positions in `active_set` starting with the maximal violating pair `mvp`:
+/// alternately the variables with the largest gradient which can be decreased and the variables with the smallest gradient which can be increased
+/// (separately for both signs if the 1-norm constraint is active).
+fn select_working_set(
+    problem: &dyn DualProblem,
+    status: &Status,
+    active_set: &[usize],
+    q: usize,
+    mvp: (usize, usize),
+) -> Vec<usize> {
+    let signs = if problem.has_max_asum() && status.asum == problem.max_asum() {
+        vec![1.0, -1.0]
+    } else {
+        vec![0.0]
+    };
+    let g = |idx: usize| status.g[active_set[idx]];
+    let mut lists = Vec::new();
+    for &sign in signs.iter() {
+        let in_group = |idx: usize| problem.sign(active_set[idx]) * sign >= 0.0;
+        let down: Vec<usize> = (0..active_set.len())
+            .filter(|&idx| in_group(idx) && status.a[active_set[idx]] > problem.lb(active_set[idx]))
+            .collect();
+        let up: Vec<usize> = (0..active_set.len())
+            .filter(|&idx| in_group(idx) && status.a[active_set[idx]] < problem.ub(active_set[idx]))
+            .collect();
+        lists.push((down, -1.0));
+        lists.push((up, 1.0));
+    }
+    for (candidates, order) in lists.iter_mut() {
+        let cmp = |&k: &usize, &l: &usize| (*order * g(k)).total_cmp(&(*order * g(l)));
+        if candidates.len() > q {
+            candidates.select_nth_unstable_by(q, cmp);
+            candidates.truncate(q);
+        }
+        candidates.sort_unstable_by(cmp);
+    }
+
+    let mut selected = vec![false; active_set.len()];
+    let mut ws = Vec::with_capacity(q);
+    for idx in [mvp.0, mvp.1] {
+        if !selected[idx] {
+            selected[idx] = true;
+            ws.push(idx);
+        }
+    }
+    let mut iters: Vec<_> = lists
+        .into_iter()
+        .map(|(list, _)| list.into_iter())
+        .collect();
+    while ws.len() < q {
+        let mut progress = false;
+        for candidates in iters.iter_mut() {
+            if ws.len() == q {
+                break;
+            }
+            if let Some(idx) = candidates.find(|&idx| !selected[idx]) {
+                selected[idx] = true;
+                ws.push(idx);
+                progress = true;
+            }
+        }
+        if !progress {
+            break;
+        }
+    }
+    ws
+}
+
+/// Finds the maximal violating pair (positions in the working set) among the variables with the given sign.
+fn find_pair(
+    problem: &dyn DualProblem,
+    status: &Status,
+    ws: &[usize],
+    g: &[f64],
+    sign: f64,
+) -> (f64, usize, usize) {
+    let mut g_max = f64::NEG_INFINITY;
+    let mut g_min = f64::INFINITY;
+    let (mut p_i, mut p_j) = (0, 0);
+    for (p, &i) in ws.iter().enumerate() {
+        if problem.sign(i) * sign < 0.0 {
+            continue;
+        }
+        if status.a[i] > problem.lb(i) && g[p] > g_max {
+            g_max = g[p];
+            p_i = p;
+        }
+        if status.a[i] < problem.ub(i) && g[p] < g_min {
+            g_min = g[p];
+            p_j = p;
+        }
+    }
+    (g_max - g_min, p_i, p_j)
+}
+
+/// Optimizes the variables of a working set of size `q` (containing the maximal violating pair `mvp`) by inner SMO steps using only the `q` rows of the working set.
+///
+/// The product `ka` is only updated for the working set during the inner steps and for all active variables afterwards.
+pub fn update_working_set(
+    problem: &dyn DualProblem,
+    kernel: &mut dyn Kernel,
+    q: usize,
+    tol: f64,
+    mvp: (usize, usize),
+    status: &mut Status,
+    active_set: &[usize],
+) {
+    let ws_idx = select_working_set(problem, status, active_set, q, mvp);
+    let ws: Vec<usize> = ws_idx.iter().map(|&idx| active_set[idx]).collect();
+    let lambda = problem.lambda();
+    kernel.use_rows(&ws, active_set, &mut |kw: Vec<&[f64]>| {
+        let m = ws.len();
+        let a_old: Vec<f64> = ws.iter().map(|&i| status.a[i]).collect();
+        let mut ka_w: Vec<f64> = ws.iter().map(|&i| status.ka[i]).collect();
+        let mut g = vec![0.0; m];
+        for _inner_step in 0..INNER_STEPS_PER_VARIABLE * m {
+            for (p, &i) in ws.iter().enumerate() {
+                g[p] = ka_w[p] + problem.d_dloss(i, status.a[i]);
+            }
+            let (violation, p_i, p_j) =
+                if problem.has_max_asum() && status.asum == problem.max_asum() {
+                    let pos = find_pair(problem, status, &ws, &g, 1.0);
+                    let neg = find_pair(problem, status, &ws, &g, -1.0);
+                    if pos.0 >= neg.0 {
+                        pos
+                    } else {
+                        neg
+                    }
+                } else {
+                    find_pair(problem, status, &ws, &g, 0.0)
+                };
+            if lambda * violation < 0.1 * tol {
+                break;
+            }
+            let (i, j) = (ws[p_i], ws[p_j]);
+            let mut max_t = f64::min(status.a[i] - problem.lb(i), problem.ub(j) - status.a[j]);
+            let max_t_asum = 0.5 * (problem.max_asum() - status.asum);
+            let update_asum = problem.has_max_asum() && problem.sign(i) != problem.sign(j);
+            // only steps with sign(i) < 0 increase the 1-norm
+            let increase_asum = update_asum && problem.sign(i) < 0.0;
+            if increase_asum && max_t > max_t_asum {
+                max_t = max_t_asum;
+            }
+            let (ki, kj) = (kw[p_i], kw[p_j]);
+            let step = compute_step(
+                problem,
+                Subproblem {
+                    ij: (i, j),
+                    max_t,
+                    q0: (ki[ws_idx[p_i]] + kj[ws_idx[p_j]] - 2.0 * ki[ws_idx[p_j]]) / lambda,
+                    p0: ka_w[p_i] - ka_w[p_j],
+                },
+                status,
+            );
+            let t = step.t;
+            if t <= 0.0 {
+                break;
+            }
+            if update_asum {
+                if increase_asum && t == max_t_asum {
+                    status.asum = problem.max_asum();
+                } else {
+                    status.asum -= 2.0 * t * problem.sign(i);
+                }
+            }
+            status.a[i] -= t;
+            status.a[j] += t;
+            status.value -= step.dvalue;
+            for (ka_r, &idx_r) in ka_w.iter_mut().zip(ws_idx.iter()) {
+                *ka_r += t / lambda * (kj[idx_r] - ki[idx_r]);
+            }
+        }
+        // update kernel product of all active variables
+        for (p, &i) in ws.iter().enumerate() {
+            let da = status.a[i] - a_old[p];
+            if da == 0.0 {
+                continue;
+            }
+            for (idx, &k) in active_set.iter().enumerate() {
+                status.ka[k] += da / lambda * kw[p][idx];
+            }
+        }
+    });
+}
@@ -21,6 +21,9 @@ pub struct Params {
     pub shrinking_threshold: f64,
     /// Time limit (in seconds)
     pub time_limit: f64,
+    /// Number of variables optimized in each step:
+    /// `2` leads to the classic SMO method, larger values to a decomposition method solving each subproblem by inner SMO steps on the cached rows of the working set.
+    pub working_set_size: usize,
 }
 
 impl Params {
@@ -35,6 +38,7 @@ impl Params {
             shrinking_period: 0,
             shrinking_threshold: 1.0,
             time_limit: f64::INFINITY,
+            working_set_size: 2,
         }
     }
 
@@ -67,4 +71,14 @@ impl Params {
         self.shrinking_period = shrinking_period;
         self
     }
+
+    /// Updates the size of the working set.
+    pub fn with_working_set_size(mut self, working_set_size: usize) -> Self {
+        assert!(
+            working_set_size >= 2,
+            "working set should contain at least two variables"
+        );
+        self.working_set_size = working_set_size;
+        self
+    }
 }
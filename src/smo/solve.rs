@@ -3,6 +3,7 @@ use crate::problem::DualProblem;
 use crate::status::{Status, StatusCode};
 use crate::time::{now, until_now};
 
+use super::decomposition::update_working_set;
 use super::update::update;
 use super::ws::*;
 use super::Params;
@@ -135,6 +136,21 @@ pub fn solve_with_status(
             break;
         }
 
+        // optimize larger working set
+        if params.working_set_size > 2 {
+            update_working_set(
+                problem,
+                kernel,
+                params.working_set_size,
+                params.tol,
+                (idx_i0, idx_j1),
+                &mut status,
+                &active_set,
+            );
+            step += 1;
+            continue;
+        }
+
         // determine working set
         last_ij = if params.second_order {
             let sign = if problem.has_max_asum() && status.asum == problem.max_asum() {
@@ -161,3 +177,54 @@ pub fn solve_with_status(
     }
     status
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::gaussian;
+    use crate::problem::{Classification, Params as ProblemParams, Regression};
+    use crate::testing::{classification, regression, rows};
+
+    /// Checks that larger working sets reach the same optimum as classic SMO (and respect the 1-norm constraint).
+    fn check_working_set(problem: &dyn DualProblem, x: &[Vec<f64>]) {
+        let mut kernel = gaussian::from_vecs(rows(x), 1.0);
+        let params = Params::new().with_tol(1e-8).with_max_steps(1000000);
+        let reference = solve(problem, &mut kernel, &params, None);
+        assert!(matches!(reference.opt_status.code, StatusCode::Optimal));
+        if problem.has_max_asum() {
+            // the 1-norm constraint should be binding
+            assert!((reference.asum - problem.max_asum()).abs() < 1e-10);
+        }
+        for working_set_size in [4, 10] {
+            let params = params.clone().with_working_set_size(working_set_size);
+            let status = solve(problem, &mut kernel, &params, None);
+            assert!(matches!(status.opt_status.code, StatusCode::Optimal));
+            assert!((status.value - reference.value).abs() < 1e-6 * (1.0 + reference.value.abs()));
+            assert!((problem.objective(&status) + status.value).abs() < 1e-8);
+            if problem.has_max_asum() {
+                let asum: f64 = status.a.iter().map(|ai| ai.abs()).sum();
+                assert!((status.asum - asum).abs() < 1e-10);
+                assert!(asum <= problem.max_asum() + 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn working_set_agrees_with_smo() {
+        let (x, y) = classification(40);
+        for max_asum in [f64::INFINITY, 1.0] {
+            let params = ProblemParams::new()
+                .with_lambda(0.1)
+                .with_max_asum(max_asum);
+            check_working_set(&Classification::new(&y, params), &x);
+        }
+
+        let (x, y) = regression(40);
+        for max_asum in [f64::INFINITY, 1.0] {
+            let params = ProblemParams::new()
+                .with_lambda(0.1)
+                .with_max_asum(max_asum);
+            check_working_set(&Regression::new(&y, params).with_epsilon(0.1), &x);
+        }
+    }
+}
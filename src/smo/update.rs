@@ -1,3 +1,4 @@
+use super::chunks::map_chunks_mut;
 use super::subproblem::{compute_step, Subproblem};
 use crate::kernel::Kernel;
 use crate::problem::DualProblem;
@@ -53,8 +54,10 @@ pub fn update(
         status.a[i] -= t;
         status.a[j] += t;
         status.value -= step.dvalue;
-        for (idx, &k) in active_set.iter().enumerate() {
-            status.ka[k] += t / problem.lambda() * (kj[idx] - ki[idx]);
-        }
+        map_chunks_mut(&mut status.ka, active_set, |range, ka_part, offset| {
+            for idx in range {
+                ka_part[active_set[idx] - offset] += t / problem.lambda() * (kj[idx] - ki[idx]);
+            }
+        });
     });
 }
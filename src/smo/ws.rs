@@ -1,3 +1,4 @@
+use super::chunks::{map_chunks, map_chunks_mut, max_first, min_first};
 use super::subproblem::{compute_step, Subproblem};
 use crate::kernel::Kernel;
 use crate::problem::DualProblem;
@@ -9,24 +10,32 @@ fn find_mvp_signed(
     active_set: &Vec<usize>,
     sign: f64,
 ) -> (f64, f64, usize, usize) {
-    let mut g_min = f64::INFINITY;
-    let mut g_max = f64::NEG_INFINITY;
-    let mut idx_i: usize = 0;
-    let mut idx_j: usize = 0;
-    for (idx, &i) in active_set.iter().enumerate() {
-        let g_i = status.ka[i] + problem.d_dloss(i, status.a[i]);
-        status.g[i] = g_i;
-        if problem.sign(i) * sign >= 0.0 {
-            if status.a[i] > problem.lb(i) && g_i > g_max {
-                idx_i = idx;
-                g_max = g_i;
-            }
-            if status.a[i] < problem.ub(i) && g_i < g_min {
-                idx_j = idx;
-                g_min = g_i;
+    let g_a = &status.a;
+    let chunks = map_chunks_mut(&mut status.g, active_set, |range, g_part, offset| {
+        let mut max_i = (f64::NEG_INFINITY, 0);
+        let mut min_j = (f64::INFINITY, 0);
+        for idx in range {
+            let i = active_set[idx];
+            let g_i = status.ka[i] + problem.d_dloss(i, g_a[i]);
+            g_part[i - offset] = g_i;
+            if problem.sign(i) * sign >= 0.0 {
+                if g_a[i] > problem.lb(i) && g_i > max_i.0 {
+                    max_i = (g_i, idx);
+                }
+                if g_a[i] < problem.ub(i) && g_i < min_j.0 {
+                    min_j = (g_i, idx);
+                }
             }
         }
-    }
+        (max_i, min_j)
+    });
+    let (max_i, min_j) = chunks.into_iter().fold(
+        ((f64::NEG_INFINITY, 0), (f64::INFINITY, 0)),
+        |(max_i, min_j), (chunk_max_i, chunk_min_j)| {
+            (max_first(max_i, chunk_max_i), min_first(min_j, chunk_min_j))
+        },
+    );
+    let ((g_max, idx_i), (g_min, idx_j)) = (max_i, min_j);
     (g_max - g_min, g_max + g_min, idx_i, idx_j)
 }
 
@@ -68,10 +77,8 @@ pub fn find_ws2(
     let j1 = active_set[idx_j1];
     let gi0 = status.g[i0];
     let gj1 = status.g[j1];
-    let mut max_d0 = 0.0;
-    let mut max_d1 = 0.0;
-    let mut idx_j0 = idx_j1;
-    let mut idx_i1 = idx_i0;
+    let mut best0 = (0.0, idx_j1);
+    let mut best1 = (0.0, idx_i0);
 
     let diags: Vec<f64> = active_set.iter().map(|&i| kernel.diag(i)).collect();
     kernel.use_rows([i0, j1].as_slice(), &active_set, &mut |kij: Vec<&[f64]>| {
@@ -82,52 +89,61 @@ pub fn find_ws2(
         let max_ti0 = status.a[i0] - problem.lb(i0);
         let max_tj1 = problem.ub(j1) - status.a[j1];
 
-        for (idx_r, &r) in active_set.iter().enumerate() {
-            if sign * problem.sign(r) < 0.0 {
-                continue;
-            }
-            let gr = status.g[r];
-            let krr = diags[idx_r];
+        let chunks = map_chunks(active_set.len(), |range| {
+            let mut best0 = (0.0, idx_j1);
+            let mut best1 = (0.0, idx_i0);
+            for idx_r in range {
+                let r = active_set[idx_r];
+                if sign * problem.sign(r) < 0.0 {
+                    continue;
+                }
+                let gr = status.g[r];
+                let krr = diags[idx_r];
 
-            let pi0r = gi0 - gr;
-            let d_upr = problem.ub(r) - status.a[r];
-            if d_upr > 0.0 && pi0r > 0.0 {
-                let step = compute_step(
-                    problem,
-                    Subproblem {
-                        ij: (i0, r),
-                        max_t: f64::min(max_ti0, d_upr),
-                        q0: ki0i0 + krr - 2.0 * ki0[idx_r],
-                        p0: status.ka[i0] - status.ka[r],
-                    },
-                    status,
-                );
-                if step.dvalue > max_d0 {
-                    idx_j0 = idx_r;
-                    max_d0 = step.dvalue;
+                let pi0r = gi0 - gr;
+                let d_upr = problem.ub(r) - status.a[r];
+                if d_upr > 0.0 && pi0r > 0.0 {
+                    let step = compute_step(
+                        problem,
+                        Subproblem {
+                            ij: (i0, r),
+                            max_t: f64::min(max_ti0, d_upr),
+                            q0: ki0i0 + krr - 2.0 * ki0[idx_r],
+                            p0: status.ka[i0] - status.ka[r],
+                        },
+                        status,
+                    );
+                    if step.dvalue > best0.0 {
+                        best0 = (step.dvalue, idx_r);
+                    }
                 }
-            }
 
-            let prj1 = gr - gj1;
-            let d_dnr = status.a[r] - problem.lb(r);
-            if d_dnr > 0.0 && prj1 > 0.0 {
-                let step = compute_step(
-                    problem,
-                    Subproblem {
-                        ij: (r, j1),
-                        max_t: f64::min(max_tj1, d_dnr),
-                        q0: kj1j1 + krr - 2.0 * kj1[idx_r],
-                        p0: status.ka[r] - status.ka[j1],
-                    },
-                    status,
-                );
-                if step.dvalue > max_d1 {
-                    idx_i1 = idx_r;
-                    max_d1 = step.dvalue;
+                let prj1 = gr - gj1;
+                let d_dnr = status.a[r] - problem.lb(r);
+                if d_dnr > 0.0 && prj1 > 0.0 {
+                    let step = compute_step(
+                        problem,
+                        Subproblem {
+                            ij: (r, j1),
+                            max_t: f64::min(max_tj1, d_dnr),
+                            q0: kj1j1 + krr - 2.0 * kj1[idx_r],
+                            p0: status.ka[r] - status.ka[j1],
+                        },
+                        status,
+                    );
+                    if step.dvalue > best1.0 {
+                        best1 = (step.dvalue, idx_r);
+                    }
                 }
             }
+            (best0, best1)
+        });
+        for (chunk_best0, chunk_best1) in chunks.into_iter() {
+            best0 = max_first(best0, chunk_best0);
+            best1 = max_first(best1, chunk_best1);
         }
     });
+    let ((max_d0, idx_j0), (max_d1, idx_i1)) = (best0, best1);
     if max_d0 > max_d1 {
         (idx_i0, idx_j0)
     } else {
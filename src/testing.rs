@@ -0,0 +1,48 @@
+//! Shared fixtures of the unit tests
+
+/// Returns `n` two-dimensional samples `(sin(0.7 i), cos(1.3 i))`.
+pub(crate) fn samples(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| vec![(i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()])
+        .collect()
+}
+
+/// Returns the samples as slices (e.g., for [`gaussian::from_vecs`](crate::kernel::gaussian::from_vecs)).
+pub(crate) fn rows(x: &[Vec<f64>]) -> Vec<&[f64]> {
+    x.iter().map(|xi| xi.as_slice()).collect()
+}
+
+/// Returns `n` samples (see [`samples`]) with labels `±1` separated by the line `x₀ + 0.5 x₁ = 0.2`.
+pub(crate) fn classification(n: usize) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let x = samples(n);
+    let y = x
+        .iter()
+        .map(|xi| if xi[0] + 0.5 * xi[1] > 0.2 { 1.0 } else { -1.0 })
+        .collect();
+    (x, y)
+}
+
+/// Returns `n` samples (see [`samples`]) with noisy real-valued labels.
+pub(crate) fn regression(n: usize) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let x = samples(n);
+    let y = x
+        .iter()
+        .enumerate()
+        .map(|(i, xi)| xi[0] + 0.5 * xi[1] + 0.3 * (i as f64 * 5.1).sin())
+        .collect();
+    (x, y)
+}
+
+/// Evaluates the polynomial kernel `(1 + u·v)²`, whose diagonal is not constant.
+pub(crate) fn polynomial(u: &[f64], v: &[f64]) -> f64 {
+    (1.0 + crate::linalg::dot(u, v)).powi(2)
+}
+
+/// Returns the polynomial kernel matrix (see [`polynomial`]) of the samples.
+pub(crate) fn polynomial_kernel(x: &[Vec<f64>]) -> crate::kernel::RowKernel<Vec<f64>> {
+    crate::kernel::RowKernel::new(
+        x.to_vec(),
+        Box::new(|u, v| polynomial(u, v)),
+        Box::new(|u| polynomial(u, u)),
+    )
+}